@@ -0,0 +1,484 @@
+//! Lightweight AST of GraphQL documents produced by [`parse`](crate::parse)
+//!
+//! Nodes are allocated in a [`Bump`](bumpalo::Bump) arena and borrow names and values from the source,
+//! so the AST is cheap to build and is dropped at once with the arena.
+//! Every node has the [`Span`] of its source, including its description.
+
+use bumpalo::Bump;
+use bumpalo::collections::{String as BumpaloString, Vec};
+use logos::Span;
+
+use crate::block_string::{BlockStringLines, dedent_block_lines_mut};
+
+/// [Document](https://spec.graphql.org/October2021/#Document)
+#[derive(Debug)]
+pub struct Document<'a> {
+    pub definitions: Vec<'a, Definition<'a>>,
+    pub span: Span,
+}
+
+/// Top-level definition of a [`Document`]
+#[derive(Debug)]
+pub enum Definition<'a> {
+    Operation(OperationDefinition<'a>),
+    Fragment(FragmentDefinition<'a>),
+    Schema(SchemaDefinition<'a>),
+    Type(TypeDefinition<'a>),
+    Directive(DirectiveDefinition<'a>),
+}
+
+impl Definition<'_> {
+    pub fn span(&self) -> &Span {
+        match self {
+            Definition::Operation(definition) => &definition.span,
+            Definition::Fragment(definition) => &definition.span,
+            Definition::Schema(definition) => &definition.span,
+            Definition::Type(definition) => &definition.span,
+            Definition::Directive(definition) => &definition.span,
+        }
+    }
+}
+
+/// Name without the `$` or `@` sigil of variables and directives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Name<'a> {
+    pub value: &'a str,
+    pub span: Span,
+}
+
+/// String or block string, as written in the source including quotes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringValue<'a> {
+    pub raw: &'a str,
+    pub block: bool,
+    pub span: Span,
+}
+
+impl StringValue<'_> {
+    /// Returns the value of the string with escape sequences resolved
+    /// and the indentation of a block string removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use graphql_minify::ast::StringValue;
+    ///
+    /// let string = StringValue { raw: r#""caf\u00E9 \"menu\"""#, block: false, span: 0..22 };
+    /// assert_eq!(string.value(), r#"café "menu""#);
+    ///
+    /// let block = StringValue { raw: "\"\"\"\n    Use `url`\n      instead\n\"\"\"", block: true, span: 0..30 };
+    /// assert_eq!(block.value(), "Use `url`\n  instead");
+    /// ```
+    pub fn value(&self) -> String {
+        if self.block {
+            block_string_value(&self.raw[3..self.raw.len() - 3])
+        } else {
+            string_value(&self.raw[1..self.raw.len() - 1])
+        }
+    }
+}
+
+/// resolves escape sequences of a string, which are already validated by the lexer
+fn string_value(raw: &str) -> String {
+    let mut value = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('b') => value.push('\u{8}'),
+            Some('f') => value.push('\u{c}'),
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('t') => value.push('\t'),
+            Some('u') => {
+                let code = if chars.as_str().starts_with('{') {
+                    let end = chars.as_str().find('}').unwrap_or(chars.as_str().len());
+                    let code = u32::from_str_radix(&chars.as_str()[1..end], 16).unwrap_or(0xFFFD);
+                    chars = chars.as_str()[(end + 1).min(chars.as_str().len())..].chars();
+                    code
+                } else {
+                    let code = hex4(chars.as_str());
+                    chars = chars.as_str()[4.min(chars.as_str().len())..].chars();
+
+                    // a leading surrogate is combined with the following trailing one
+                    if (0xD800..0xDC00).contains(&code)
+                        && let Some(trailing) = chars.as_str().strip_prefix("\\u")
+                        && let trailing_code = hex4(trailing)
+                        && (0xDC00..0xE000).contains(&trailing_code)
+                    {
+                        chars = trailing[4..].chars();
+                        0x10000 + ((code - 0xD800) << 10) + (trailing_code - 0xDC00)
+                    } else {
+                        code
+                    }
+                };
+
+                value.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+            }
+            Some(c) => value.push(c),
+            None => {}
+        }
+    }
+
+    value
+}
+
+fn hex4(raw: &str) -> u32 {
+    raw.get(..4)
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .unwrap_or(0xFFFD)
+}
+
+/// removes the common indentation and leading and trailing blank lines of a block string
+fn block_string_value(raw: &str) -> String {
+    let arena = Bump::new();
+    let raw = raw.replace("\r\n", "\n").replace('\r', "\n");
+    let mut lines = BlockStringLines::with_capacity_in(4, &arena);
+
+    for line in raw.split('\n') {
+        lines.push(BumpaloString::from_str_in(
+            &line.replace("\\\"\"\"", "\"\"\""),
+            &arena,
+        ));
+    }
+
+    dedent_block_lines_mut(&mut lines);
+
+    lines
+        .iter()
+        .map(BumpaloString::as_str)
+        .collect::<std::vec::Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationType {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+impl OperationType {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            OperationType::Query => "query",
+            OperationType::Mutation => "mutation",
+            OperationType::Subscription => "subscription",
+        }
+    }
+
+    pub(crate) fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "query" => Some(OperationType::Query),
+            "mutation" => Some(OperationType::Mutation),
+            "subscription" => Some(OperationType::Subscription),
+            _ => None,
+        }
+    }
+}
+
+/// [OperationDefinition](https://spec.graphql.org/October2021/#OperationDefinition),
+/// the query shorthand has no name and [`OperationType::Query`]
+#[derive(Debug)]
+pub struct OperationDefinition<'a> {
+    /// only with [`Dialect::operation_descriptions`](crate::Dialect::operation_descriptions)
+    pub description: Option<StringValue<'a>>,
+    pub operation: OperationType,
+    pub name: Option<Name<'a>>,
+    pub variable_definitions: Vec<'a, VariableDefinition<'a>>,
+    pub directives: Vec<'a, Directive<'a>>,
+    pub selection_set: SelectionSet<'a>,
+    pub span: Span,
+}
+
+impl OperationDefinition<'_> {
+    /// Returns the span of the operation keyword.
+    ///
+    /// The keyword is not kept in the AST, so the opening brace of the selection set
+    /// is returned for the query shorthand and operations with a description.
+    pub fn keyword_span(&self) -> Span {
+        let start = self.span.start;
+
+        if self.description.is_some() || self.selection_set.span.start == start {
+            let start = self.selection_set.span.start;
+            start..start + 1
+        } else {
+            start..start + self.operation.as_str().len()
+        }
+    }
+}
+
+/// [FragmentDefinition](https://spec.graphql.org/October2021/#FragmentDefinition)
+#[derive(Debug)]
+pub struct FragmentDefinition<'a> {
+    /// only with [`Dialect::operation_descriptions`](crate::Dialect::operation_descriptions)
+    pub description: Option<StringValue<'a>>,
+    pub name: Name<'a>,
+    /// only with [`Dialect::fragment_arguments`](crate::Dialect::fragment_arguments)
+    pub variable_definitions: Vec<'a, VariableDefinition<'a>>,
+    pub type_condition: Name<'a>,
+    pub directives: Vec<'a, Directive<'a>>,
+    pub selection_set: SelectionSet<'a>,
+    pub span: Span,
+}
+
+/// [VariableDefinition](https://spec.graphql.org/October2021/#VariableDefinition)
+#[derive(Debug)]
+pub struct VariableDefinition<'a> {
+    pub variable: Name<'a>,
+    pub ty: Type<'a>,
+    pub default_value: Option<Value<'a>>,
+    pub directives: Vec<'a, Directive<'a>>,
+    pub span: Span,
+}
+
+/// [Type](https://spec.graphql.org/October2021/#Type) reference
+#[derive(Debug)]
+pub enum Type<'a> {
+    Named(Name<'a>),
+    List(&'a Type<'a>, Span),
+    NonNull(&'a Type<'a>, Span),
+}
+
+impl<'a> Type<'a> {
+    pub fn span(&self) -> &Span {
+        match self {
+            Type::Named(name) => &name.span,
+            Type::List(_, span) | Type::NonNull(_, span) => span,
+        }
+    }
+
+    /// returns the name of the innermost named type
+    pub fn named(&self) -> &Name<'a> {
+        match self {
+            Type::Named(name) => name,
+            Type::List(ty, _) | Type::NonNull(ty, _) => ty.named(),
+        }
+    }
+}
+
+/// [SelectionSet](https://spec.graphql.org/October2021/#SelectionSet)
+#[derive(Debug)]
+pub struct SelectionSet<'a> {
+    pub selections: Vec<'a, Selection<'a>>,
+    pub span: Span,
+}
+
+#[derive(Debug)]
+pub enum Selection<'a> {
+    Field(Field<'a>),
+    FragmentSpread(FragmentSpread<'a>),
+    InlineFragment(InlineFragment<'a>),
+}
+
+impl Selection<'_> {
+    pub fn span(&self) -> &Span {
+        match self {
+            Selection::Field(field) => &field.span,
+            Selection::FragmentSpread(spread) => &spread.span,
+            Selection::InlineFragment(fragment) => &fragment.span,
+        }
+    }
+}
+
+/// [Field](https://spec.graphql.org/October2021/#Field)
+#[derive(Debug)]
+pub struct Field<'a> {
+    pub alias: Option<Name<'a>>,
+    pub name: Name<'a>,
+    pub arguments: Vec<'a, Argument<'a>>,
+    /// only with [`Dialect::client_controlled_nullability`](crate::Dialect::client_controlled_nullability)
+    pub nullability: Option<Nullability<'a>>,
+    pub directives: Vec<'a, Directive<'a>>,
+    pub selection_set: Option<SelectionSet<'a>>,
+    pub span: Span,
+}
+
+/// Nullability designator of [client-controlled nullability](https://github.com/graphql/graphql-spec/issues/867)
+#[derive(Debug)]
+pub enum Nullability<'a> {
+    /// `!`
+    Required(Span),
+    /// `?`
+    Optional(Span),
+    /// `[…]` with the nullability of elements, optionally followed by `!` or `?`
+    List {
+        element: Option<&'a Nullability<'a>>,
+        designator: Option<&'a Nullability<'a>>,
+        span: Span,
+    },
+}
+
+/// [FragmentSpread](https://spec.graphql.org/October2021/#FragmentSpread)
+#[derive(Debug)]
+pub struct FragmentSpread<'a> {
+    pub name: Name<'a>,
+    /// only with [`Dialect::fragment_arguments`](crate::Dialect::fragment_arguments)
+    pub arguments: Vec<'a, Argument<'a>>,
+    pub directives: Vec<'a, Directive<'a>>,
+    pub span: Span,
+}
+
+/// [InlineFragment](https://spec.graphql.org/October2021/#InlineFragment)
+#[derive(Debug)]
+pub struct InlineFragment<'a> {
+    pub type_condition: Option<Name<'a>>,
+    pub directives: Vec<'a, Directive<'a>>,
+    pub selection_set: SelectionSet<'a>,
+    pub span: Span,
+}
+
+/// [Argument](https://spec.graphql.org/October2021/#Argument)
+#[derive(Debug)]
+pub struct Argument<'a> {
+    pub name: Name<'a>,
+    pub value: Value<'a>,
+    pub span: Span,
+}
+
+/// [Directive](https://spec.graphql.org/October2021/#Directive)
+#[derive(Debug)]
+pub struct Directive<'a> {
+    pub name: Name<'a>,
+    pub arguments: Vec<'a, Argument<'a>>,
+    pub span: Span,
+}
+
+/// [Value](https://spec.graphql.org/October2021/#Value), numbers are kept as written in the source
+#[derive(Debug)]
+pub enum Value<'a> {
+    Variable(Name<'a>),
+    Int(&'a str, Span),
+    Float(&'a str, Span),
+    String(StringValue<'a>),
+    Boolean(bool, Span),
+    Null(Span),
+    Enum(Name<'a>),
+    List(Vec<'a, Value<'a>>, Span),
+    Object(Vec<'a, ObjectField<'a>>, Span),
+}
+
+impl Value<'_> {
+    pub fn span(&self) -> &Span {
+        match self {
+            Value::Variable(name) | Value::Enum(name) => &name.span,
+            Value::String(string) => &string.span,
+            Value::Int(_, span)
+            | Value::Float(_, span)
+            | Value::Boolean(_, span)
+            | Value::Null(span)
+            | Value::List(_, span)
+            | Value::Object(_, span) => span,
+        }
+    }
+}
+
+/// [ObjectField](https://spec.graphql.org/October2021/#ObjectField)
+#[derive(Debug)]
+pub struct ObjectField<'a> {
+    pub name: Name<'a>,
+    pub value: Value<'a>,
+    pub span: Span,
+}
+
+/// [SchemaDefinition](https://spec.graphql.org/October2021/#SchemaDefinition)
+/// or [SchemaExtension](https://spec.graphql.org/October2021/#SchemaExtension)
+#[derive(Debug)]
+pub struct SchemaDefinition<'a> {
+    pub description: Option<StringValue<'a>>,
+    pub extension: bool,
+    pub directives: Vec<'a, Directive<'a>>,
+    pub operation_types: Vec<'a, RootOperationType<'a>>,
+    pub span: Span,
+}
+
+/// [RootOperationTypeDefinition](https://spec.graphql.org/October2021/#RootOperationTypeDefinition)
+#[derive(Debug)]
+pub struct RootOperationType<'a> {
+    pub operation: OperationType,
+    pub ty: Name<'a>,
+    pub span: Span,
+}
+
+/// [TypeDefinition](https://spec.graphql.org/October2021/#TypeDefinition)
+/// or [TypeExtension](https://spec.graphql.org/October2021/#TypeExtension)
+#[derive(Debug)]
+pub struct TypeDefinition<'a> {
+    pub description: Option<StringValue<'a>>,
+    pub extension: bool,
+    pub name: Name<'a>,
+    pub directives: Vec<'a, Directive<'a>>,
+    pub kind: TypeKind<'a>,
+    pub span: Span,
+}
+
+/// Kind-specific part of [`TypeDefinition`]
+#[derive(Debug)]
+pub enum TypeKind<'a> {
+    Scalar,
+    Object {
+        interfaces: Vec<'a, Name<'a>>,
+        fields: Vec<'a, FieldDefinition<'a>>,
+    },
+    Interface {
+        interfaces: Vec<'a, Name<'a>>,
+        fields: Vec<'a, FieldDefinition<'a>>,
+    },
+    Union {
+        members: Vec<'a, Name<'a>>,
+    },
+    Enum {
+        values: Vec<'a, EnumValueDefinition<'a>>,
+    },
+    InputObject {
+        fields: Vec<'a, InputValueDefinition<'a>>,
+    },
+}
+
+/// [FieldDefinition](https://spec.graphql.org/October2021/#FieldDefinition)
+#[derive(Debug)]
+pub struct FieldDefinition<'a> {
+    pub description: Option<StringValue<'a>>,
+    pub name: Name<'a>,
+    pub arguments: Vec<'a, InputValueDefinition<'a>>,
+    pub ty: Type<'a>,
+    pub directives: Vec<'a, Directive<'a>>,
+    pub span: Span,
+}
+
+/// [InputValueDefinition](https://spec.graphql.org/October2021/#InputValueDefinition)
+/// of arguments and input fields
+#[derive(Debug)]
+pub struct InputValueDefinition<'a> {
+    pub description: Option<StringValue<'a>>,
+    pub name: Name<'a>,
+    pub ty: Type<'a>,
+    pub default_value: Option<Value<'a>>,
+    pub directives: Vec<'a, Directive<'a>>,
+    pub span: Span,
+}
+
+/// [EnumValueDefinition](https://spec.graphql.org/October2021/#EnumValueDefinition)
+#[derive(Debug)]
+pub struct EnumValueDefinition<'a> {
+    pub description: Option<StringValue<'a>>,
+    pub name: Name<'a>,
+    pub directives: Vec<'a, Directive<'a>>,
+    pub span: Span,
+}
+
+/// [DirectiveDefinition](https://spec.graphql.org/October2021/#DirectiveDefinition)
+#[derive(Debug)]
+pub struct DirectiveDefinition<'a> {
+    pub description: Option<StringValue<'a>>,
+    pub name: Name<'a>,
+    pub arguments: Vec<'a, InputValueDefinition<'a>>,
+    pub repeatable: bool,
+    pub locations: Vec<'a, Name<'a>>,
+    pub span: Span,
+}
@@ -0,0 +1,370 @@
+//! Catalogue of stable error codes
+//!
+//! Every [`MinifyError`](crate::MinifyError) and [`ValidationError`](crate::ValidationError) variant,
+//! as well as [`Deprecation`](crate::Deprecation) warnings and [`LintRule`](crate::LintRule)s, has a code that never changes between versions,
+//! so that tools can link errors to their documentation
+//!
+//! Codes from `GQLMIN101` belong to diagnostics of the `swc-plugin-minify-graphql` SWC plugin,
+//! which report literals of a module and list the errors of the documents in them
+
+/// Description of an error code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorCode {
+    /// stable code, e.g. `GQLMIN001`
+    pub code: &'static str,
+    /// short description of the error
+    pub message: &'static str,
+    /// explanation of the error and how to fix it
+    pub explanation: &'static str,
+}
+
+/// [`MinifyError::UnknownToken`](crate::MinifyError::UnknownToken)
+pub const UNKNOWN_TOKEN: ErrorCode = ErrorCode {
+    code: "GQLMIN001",
+    message: "unknown token",
+    explanation: "The document contains characters that do not form any GraphQL token, \
+        e.g. a string missing its closing quote. \
+        Remove the characters or replace them with valid GraphQL syntax.",
+};
+
+/// [`MinifyError::UnterminatedString`](crate::MinifyError::UnterminatedString)
+pub const UNTERMINATED_STRING: ErrorCode = ErrorCode {
+    code: "GQLMIN002",
+    message: "unterminated string",
+    explanation: "A string contains a line break before its closing quote. \
+        Strings cannot span several lines, use a block string (`\"\"\"`) instead.",
+};
+
+/// [`MinifyError::InvalidEscapeSequence`](crate::MinifyError::InvalidEscapeSequence)
+pub const INVALID_ESCAPE_SEQUENCE: ErrorCode = ErrorCode {
+    code: "GQLMIN003",
+    message: "invalid escape sequence",
+    explanation: "A string contains an escape sequence that GraphQL does not support. \
+        Only `\\\"`, `\\\\`, `\\/`, `\\b`, `\\f`, `\\n`, `\\r`, `\\t` and `\\u` escapes are allowed.",
+};
+
+/// [`MinifyError::UnterminatedBlockString`](crate::MinifyError::UnterminatedBlockString)
+pub const UNTERMINATED_BLOCK_STRING: ErrorCode = ErrorCode {
+    code: "GQLMIN004",
+    message: "unterminated block string",
+    explanation: "A block string is missing its closing `\"\"\"`. Add the closing delimiter.",
+};
+
+/// [`MinifyError::InvalidNumber`](crate::MinifyError::InvalidNumber)
+pub const INVALID_NUMBER: ErrorCode = ErrorCode {
+    code: "GQLMIN005",
+    message: "invalid number",
+    explanation: "A numeric literal is malformed, e.g. has leading zeros, lacks digits \
+        after `.` or `e`, or is directly followed by a name. Fix the literal or separate it from the name.",
+};
+
+/// [`MinifyError::UnexpectedCharacter`](crate::MinifyError::UnexpectedCharacter)
+pub const UNEXPECTED_CHARACTER: ErrorCode = ErrorCode {
+    code: "GQLMIN006",
+    message: "unexpected character",
+    explanation: "The document contains a character that cannot start any GraphQL token, \
+        e.g. `%` or a single quote. Remove the character or replace it with valid GraphQL syntax.",
+};
+
+/// [`MinifyError::InvalidUtf8`](crate::MinifyError::InvalidUtf8)
+pub const INVALID_UTF8: ErrorCode = ErrorCode {
+    code: "GQLMIN007",
+    message: "invalid UTF-8",
+    explanation: "A document passed as bytes contains a byte sequence that is not valid UTF-8. \
+        Make sure the document is read with the correct encoding.",
+};
+
+/// [`MinifyError::UnsupportedSyntax`](crate::MinifyError::UnsupportedSyntax)
+pub const UNSUPPORTED_SYNTAX: ErrorCode = ErrorCode {
+    code: "GQLMIN008",
+    message: "unsupported syntax",
+    explanation: "The document uses syntax from a GraphQL proposal, e.g. client-controlled nullability \
+        or fragment arguments, that is not enabled in the dialect. Enable the extension or remove the syntax.",
+};
+
+/// [`MinifyError::SyntaxError`](crate::MinifyError::SyntaxError)
+pub const SYNTAX_ERROR: ErrorCode = ErrorCode {
+    code: "GQLMIN009",
+    message: "syntax error",
+    explanation: "The tokens of the document do not form valid GraphQL, e.g. braces are unbalanced \
+        or an operation has no selection set. Fix the document according to the expected token.",
+};
+
+/// [`ValidationError::UnknownField`](crate::ValidationError::UnknownField)
+pub const UNKNOWN_FIELD: ErrorCode = ErrorCode {
+    code: "GQLMIN010",
+    message: "unknown field",
+    explanation: "A selection set selects a field that is not defined on its type in the schema. \
+        Check the spelling of the field or update the schema.",
+};
+
+/// [`ValidationError::UnknownType`](crate::ValidationError::UnknownType)
+pub const UNKNOWN_TYPE: ErrorCode = ErrorCode {
+    code: "GQLMIN011",
+    message: "unknown type",
+    explanation: "A variable definition or a type condition refers to a type that is not defined in the schema, \
+        or the schema has no root type of the operation. Check the spelling of the type or update the schema.",
+};
+
+/// [`ValidationError::UnknownArgument`](crate::ValidationError::UnknownArgument)
+pub const UNKNOWN_ARGUMENT: ErrorCode = ErrorCode {
+    code: "GQLMIN012",
+    message: "unknown argument",
+    explanation: "A field is passed an argument that is not defined on the field in the schema. \
+        Check the spelling of the argument or remove it.",
+};
+
+/// [`ValidationError::InvalidTypeCondition`](crate::ValidationError::InvalidTypeCondition)
+pub const INVALID_TYPE_CONDITION: ErrorCode = ErrorCode {
+    code: "GQLMIN013",
+    message: "invalid type condition",
+    explanation: "A fragment is conditioned on a type that is not an object, an interface or a union, \
+        or on a type that can never be the type of the selection set it is used in. \
+        Spread the fragment in a selection set of a matching type.",
+};
+
+/// [`ValidationError::VariableTypeMismatch`](crate::ValidationError::VariableTypeMismatch)
+pub const VARIABLE_TYPE_MISMATCH: ErrorCode = ErrorCode {
+    code: "GQLMIN014",
+    message: "variable type mismatch",
+    explanation: "A variable is used where a value of an incompatible type is expected, \
+        e.g. a nullable variable is passed to a non-null argument without a default value. \
+        Change the type of the variable to the expected one.",
+};
+
+/// [`Deprecation`](crate::Deprecation)
+pub const DEPRECATED: ErrorCode = ErrorCode {
+    code: "GQLMIN015",
+    message: "deprecated",
+    explanation: "A field, an argument, an input field or an enum value is marked `@deprecated` in the schema. \
+        This is a warning rather than an error: migrate to the replacement given in the reason \
+        before the element is removed from the schema.",
+};
+
+/// [`LintRule::AnonymousOperation`](crate::LintRule::AnonymousOperation)
+pub const ANONYMOUS_OPERATION: ErrorCode = ErrorCode {
+    code: "GQLMIN016",
+    message: "anonymous operation",
+    explanation: "An operation has no name, which makes it hard to identify in logs and monitoring \
+        of the server. Give the operation a name, e.g. `query GetUser { ... }`.",
+};
+
+/// [`LintRule::DuplicateOperationName`](crate::LintRule::DuplicateOperationName)
+pub const DUPLICATE_OPERATION_NAME: ErrorCode = ErrorCode {
+    code: "GQLMIN017",
+    message: "duplicate operation name",
+    explanation: "Another operation of the same module already has the name, \
+        so the operations cannot be told apart by the server. Rename one of the operations.",
+};
+
+/// [`LintRule::UnusedVariable`](crate::LintRule::UnusedVariable)
+pub const UNUSED_VARIABLE: ErrorCode = ErrorCode {
+    code: "GQLMIN018",
+    message: "unused variable",
+    explanation: "An operation defines a variable that is used neither in the operation \
+        nor in the fragments it spreads, which servers reject. Remove the variable definition.",
+};
+
+/// [`LintRule::UndefinedVariable`](crate::LintRule::UndefinedVariable)
+pub const UNDEFINED_VARIABLE: ErrorCode = ErrorCode {
+    code: "GQLMIN019",
+    message: "undefined variable",
+    explanation: "An operation, or a fragment it spreads, uses a variable that the operation does not define. \
+        Add the variable to the variable definitions of the operation.",
+};
+
+/// [`LintRule::UnknownFragment`](crate::LintRule::UnknownFragment)
+pub const UNKNOWN_FRAGMENT: ErrorCode = ErrorCode {
+    code: "GQLMIN020",
+    message: "unknown fragment",
+    explanation: "A fragment is spread but is not defined in the document. \
+        Define the fragment or interpolate the literal that defines it.",
+};
+
+/// Plugin diagnostic of a literal that cannot be minified
+pub const MINIFY_FAILED: ErrorCode = ErrorCode {
+    code: "GQLMIN101",
+    message: "minification failed",
+    explanation: "The literal contains errors, which are listed in the diagnostic, so it is left unchanged. \
+        Fix the errors or enable `recover` to minify the literal with invalid tokens left as is.",
+};
+
+/// Plugin diagnostic of a literal that cannot be formatted
+pub const FORMAT_FAILED: ErrorCode = ErrorCode {
+    code: "GQLMIN102",
+    message: "formatting failed",
+    explanation: "The literal contains errors, which are listed in the diagnostic, so it is left unchanged. \
+        Fix the errors to format the literal.",
+};
+
+/// Plugin diagnostic of a literal minified with invalid tokens left as is
+pub const RECOVERED: ErrorCode = ErrorCode {
+    code: "GQLMIN103",
+    message: "recovered",
+    explanation: "The literal contains errors, but `recover` is enabled, so it is minified with invalid tokens left as is. \
+        The result is likely rejected by the server, fix the errors listed in the diagnostic.",
+};
+
+/// Plugin diagnostic of definitions of a kind other than declared by the marker
+pub const KIND_MISMATCH: ErrorCode = ErrorCode {
+    code: "GQLMIN104",
+    message: "kind mismatch",
+    explanation: "The marker comment declares the kind of the document, e.g. `/* GraphQL schema */`, but the literal \
+        contains definitions of another kind, so it is left unchanged. Fix the marker or move the definitions.",
+};
+
+/// Plugin diagnostic of unknown options of a marker comment
+pub const UNKNOWN_MARKER_OPTIONS: ErrorCode = ErrorCode {
+    code: "GQLMIN105",
+    message: "unknown marker options",
+    explanation: "The marker comment contains options after `:` that are not recognized, so they are ignored. \
+        Remove the options or fix their spelling, e.g. `/* GraphQL: minify */`.",
+};
+
+/// Plugin diagnostic of a template expression breaking a token
+pub const EXPR_BREAKS_TOKEN: ErrorCode = ErrorCode {
+    code: "GQLMIN106",
+    message: "expression breaks token",
+    explanation: "A template expression is placed right next to a name or a number, so the token is split \
+        between the template and the interpolated value. Add whitespace around the expression, \
+        or pass the value as a variable if it is inside a string.",
+};
+
+/// Plugin diagnostic of a literal that is not valid GraphQL
+pub const INVALID_SYNTAX: ErrorCode = ErrorCode {
+    code: "GQLMIN107",
+    message: "invalid syntax",
+    explanation: "The tokens of the literal do not form a valid GraphQL document, the syntax errors are listed in the diagnostic. \
+        Fix the document, or set `validateSyntax` to `\"off\"` for documents that are not valid on purpose.",
+};
+
+/// Plugin diagnostic of a literal not matching the schema
+pub const SCHEMA_MISMATCH: ErrorCode = ErrorCode {
+    code: "GQLMIN108",
+    message: "schema mismatch",
+    explanation: "Operations or fragments of the literal do not match the configured schema, the errors are listed \
+        in the diagnostic. Fix the document or update the schema.",
+};
+
+/// Plugin diagnostic of a configured schema that cannot be loaded
+pub const INVALID_SCHEMA: ErrorCode = ErrorCode {
+    code: "GQLMIN109",
+    message: "invalid schema",
+    explanation: "The configured schema cannot be read or parsed, so literals are not validated against it. \
+        Check that the path is inside a directory mapped into the plugin sandbox and the schema is valid.",
+};
+
+/// Plugin diagnostic of usages of deprecated schema elements
+pub const DEPRECATED_USAGE: ErrorCode = ErrorCode {
+    code: "GQLMIN110",
+    message: "deprecated usage",
+    explanation: "The literal uses elements marked `@deprecated` in the configured schema, the usages are listed \
+        in the diagnostic. This is a warning: migrate to the replacements before the elements are removed.",
+};
+
+/// Plugin diagnostic of a literal violating lint rules
+pub const LINT: ErrorCode = ErrorCode {
+    code: "GQLMIN111",
+    message: "lint",
+    explanation: "Operations or fragments of the literal violate the enabled lint rules, the violations are listed \
+        in the diagnostic. Fix the document or change the severity of the rules.",
+};
+
+/// Plugin diagnostic of all selections of a literal removed by transforms
+pub const EMPTIED_SELECTION: ErrorCode = ErrorCode {
+    code: "GQLMIN112",
+    message: "emptied selection",
+    explanation: "The literal contains the contents of a selection set, all of which are removed by transforms, \
+        so interpolating it would leave an empty selection set. The literal is left unchanged: \
+        remove its interpolation or keep some of its selections.",
+};
+
+/// Plugin diagnostic of a given operation name used by another operation
+pub const NAME_COLLISION: ErrorCode = ErrorCode {
+    code: "GQLMIN113",
+    message: "name collision",
+    explanation: "The name given to an anonymous operation after its binding is already used by another operation \
+        of the module. Name one of the operations explicitly or rename the binding.",
+};
+
+const ERROR_CODES: &[ErrorCode] = &[
+    UNKNOWN_TOKEN,
+    UNTERMINATED_STRING,
+    INVALID_ESCAPE_SEQUENCE,
+    UNTERMINATED_BLOCK_STRING,
+    INVALID_NUMBER,
+    UNEXPECTED_CHARACTER,
+    INVALID_UTF8,
+    UNSUPPORTED_SYNTAX,
+    SYNTAX_ERROR,
+    UNKNOWN_FIELD,
+    UNKNOWN_TYPE,
+    UNKNOWN_ARGUMENT,
+    INVALID_TYPE_CONDITION,
+    VARIABLE_TYPE_MISMATCH,
+    DEPRECATED,
+    ANONYMOUS_OPERATION,
+    DUPLICATE_OPERATION_NAME,
+    UNUSED_VARIABLE,
+    UNDEFINED_VARIABLE,
+    UNKNOWN_FRAGMENT,
+    MINIFY_FAILED,
+    FORMAT_FAILED,
+    RECOVERED,
+    KIND_MISMATCH,
+    UNKNOWN_MARKER_OPTIONS,
+    EXPR_BREAKS_TOKEN,
+    INVALID_SYNTAX,
+    SCHEMA_MISMATCH,
+    INVALID_SCHEMA,
+    DEPRECATED_USAGE,
+    LINT,
+    EMPTIED_SELECTION,
+    NAME_COLLISION,
+];
+
+/// Returns all error codes in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// let codes = graphql_minify::codes::error_codes();
+///
+/// assert_eq!(codes[0].code, "GQLMIN001");
+/// ```
+pub fn error_codes() -> &'static [ErrorCode] {
+    ERROR_CODES
+}
+
+/// Returns the description of the error code, if it exists.
+///
+/// # Examples
+///
+/// ```
+/// use graphql_minify::codes::{explain, UNTERMINATED_STRING};
+///
+/// assert_eq!(explain("GQLMIN002"), Some(&UNTERMINATED_STRING));
+/// assert_eq!(explain("GQLMIN999"), None);
+/// ```
+pub fn explain(code: &str) -> Option<&'static ErrorCode> {
+    ERROR_CODES
+        .iter()
+        .find(|error_code| error_code.code == code)
+}
+
+#[cfg(test)]
+mod test {
+    use super::error_codes;
+
+    #[test]
+    fn codes_are_unique_and_sorted() {
+        let codes = error_codes();
+
+        assert!(codes.windows(2).all(|pair| pair[0].code < pair[1].code));
+        assert!(
+            codes
+                .iter()
+                .all(|error_code| error_code.code.starts_with("GQLMIN"))
+        );
+    }
+}
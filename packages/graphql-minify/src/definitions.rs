@@ -0,0 +1,187 @@
+use logos::{Logos, Span};
+
+use crate::MinifyError;
+use crate::lexer::{Token, skip_block_string};
+
+/// Kind of a top-level definition of a GraphQL document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefinitionKind {
+    /// [operation definition](https://spec.graphql.org/October2021/#OperationDefinition),
+    /// including the query shorthand
+    Operation,
+    /// [fragment definition](https://spec.graphql.org/October2021/#FragmentDefinition)
+    Fragment,
+    /// [type system definition](https://spec.graphql.org/October2021/#TypeSystemDefinition)
+    /// or [extension](https://spec.graphql.org/October2021/#TypeSystemExtension)
+    TypeSystem,
+    /// anything else, e.g. the contents of a selection set
+    Selection,
+}
+
+impl DefinitionKind {
+    pub const fn as_str(&self) -> &str {
+        match self {
+            DefinitionKind::Operation => "operation definition",
+            DefinitionKind::Fragment => "fragment definition",
+            DefinitionKind::TypeSystem => "type system definition",
+            DefinitionKind::Selection => "selection",
+        }
+    }
+
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "query" | "mutation" | "subscription" => Some(DefinitionKind::Operation),
+            "fragment" => Some(DefinitionKind::Fragment),
+            "schema" | "scalar" | "type" | "interface" | "union" | "enum" | "input"
+            | "directive" | "extend" => Some(DefinitionKind::TypeSystem),
+            _ => None,
+        }
+    }
+}
+
+/// Top-level definition of a GraphQL document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub kind: DefinitionKind,
+    /// span of the definition's first token, including its description
+    pub span: Span,
+}
+
+/// Lists top-level definitions of a GraphQL document.
+///
+/// Definitions are recognized by their leading keyword only, so the document is not validated.
+/// Everything that does not start with a keyword is considered a [`DefinitionKind::Selection`],
+/// which lasts until the end of the document.
+///
+/// # Examples
+///
+/// ```
+/// use graphql_minify::{definitions, DefinitionKind};
+///
+/// let kinds = definitions("query { ...img } fragment img on Image { id }")
+///     .unwrap()
+///     .into_iter()
+///     .map(|definition| definition.kind)
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(kinds, [DefinitionKind::Operation, DefinitionKind::Fragment]);
+/// ```
+///
+/// # Errors
+///
+/// This function will return an error if the lexing process encounters an unexpected character.
+pub fn definitions<T: AsRef<str>>(value: T) -> Result<Vec<Definition>, MinifyError> {
+    let mut lexer = Token::lexer(value.as_ref());
+    let mut definitions = Vec::<Definition>::new();
+    let mut depth = 0usize;
+    let mut last_token = None;
+    let mut description_start = None;
+
+    while let Some(token) = lexer.next() {
+        let token = match token {
+            Ok(token) => token,
+            Err(e) => return Err(MinifyError::from_lexing_error(e, &lexer)),
+        };
+
+        let span = lexer.span();
+        let is_in_selection = definitions.last().map(|d| d.kind) == Some(DefinitionKind::Selection);
+
+        if token == Token::BlockStringDelimiter {
+            skip_block_string(&mut lexer).map_err(|e| MinifyError::from_lexing_error(e, &lexer))?;
+        }
+
+        if depth == 0 && !is_in_selection {
+            let keyword_kind = (token == Token::Identifier && last_token != Some("extend"))
+                .then(|| DefinitionKind::from_keyword(lexer.slice()))
+                .flatten();
+
+            let kind = match token {
+                Token::String | Token::BlockStringDelimiter => {
+                    description_start.get_or_insert(span.start);
+                    None
+                }
+                _ if keyword_kind.is_some() => keyword_kind,
+                Token::BraceOpen if matches!(last_token, None | Some("}")) => {
+                    Some(DefinitionKind::Operation)
+                }
+                _ if matches!(last_token, None | Some("}")) => Some(DefinitionKind::Selection),
+                _ => None,
+            };
+
+            if let Some(kind) = kind {
+                definitions.push(Definition {
+                    kind,
+                    span: description_start.take().unwrap_or(span.start)..span.end,
+                });
+            }
+        }
+
+        match token {
+            Token::BraceOpen | Token::ParenOpen | Token::BracketOpen => depth += 1,
+            Token::BraceClose | Token::ParenClose | Token::BracketClose => {
+                depth = depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+
+        if depth == 0 {
+            last_token = Some(match token {
+                Token::BlockStringDelimiter => r#"""""#,
+                _ => lexer.slice(),
+            });
+        }
+    }
+
+    Ok(definitions)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DefinitionKind, definitions};
+
+    fn kinds(value: &str) -> Vec<DefinitionKind> {
+        definitions(value)
+            .unwrap()
+            .into_iter()
+            .map(|definition| definition.kind)
+            .collect()
+    }
+
+    #[test]
+    fn lists_executable_definitions() {
+        assert_eq!(
+            kinds("query Q($a: Int = 1) { a } { b } mutation { c } fragment F on T { d }"),
+            [
+                DefinitionKind::Operation,
+                DefinitionKind::Operation,
+                DefinitionKind::Operation,
+                DefinitionKind::Fragment,
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_type_system_definitions() {
+        let schema = r#"
+            """Description""" type Query { query: String }
+            extend type Query { type: Int }
+            "Description" scalar Date
+            union U = A | B
+            directive @d on FIELD | QUERY
+        "#;
+
+        assert_eq!(kinds(schema), [DefinitionKind::TypeSystem; 5]);
+        assert_eq!(definitions(schema).unwrap()[0].span, 13..35);
+    }
+
+    #[test]
+    fn treats_everything_else_as_selection() {
+        assert_eq!(kinds("id type { query }"), [DefinitionKind::Selection]);
+        assert_eq!(kinds("...F"), [DefinitionKind::Selection]);
+        assert_eq!(
+            kinds("query { a } b"),
+            [DefinitionKind::Operation, DefinitionKind::Selection]
+        );
+        assert_eq!(kinds(""), []);
+    }
+}
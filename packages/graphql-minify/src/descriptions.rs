@@ -0,0 +1,43 @@
+//! Detection of descriptions in the stream of tokens
+
+use crate::lexer::Token;
+
+/// Tells descriptions from string values
+///
+/// Only tokens are seen, so strings are considered values after `:` and `=`, e.g. in arguments,
+/// object fields and default values, and inside lists, since list types never contain strings.
+/// Any other string is a description of the following definition, field, argument or enum value.
+pub(crate) struct DescriptionDetector {
+    previous: Option<Token>,
+    /// nesting depth of brackets of list values and list types
+    brackets: usize,
+}
+
+impl DescriptionDetector {
+    pub fn new() -> Self {
+        Self {
+            previous: None,
+            brackets: 0,
+        }
+    }
+
+    /// returns whether the token is a description, the contents of block strings are not passed
+    pub fn is_description(&mut self, token: &Token) -> bool {
+        let previous = self.previous.replace(*token);
+
+        match token {
+            Token::String | Token::BlockStringDelimiter => {
+                self.brackets == 0 && !matches!(previous, Some(Token::Colon | Token::Equals))
+            }
+            Token::BracketOpen => {
+                self.brackets += 1;
+                false
+            }
+            Token::BracketClose => {
+                self.brackets = self.brackets.saturating_sub(1);
+                false
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! Syntax from GraphQL proposals that is not part of the spec
+
+use logos::Span;
+
+use crate::MinifyError;
+use crate::lexer::Token;
+
+/// Experimental syntax accepted in addition to the [October 2021 spec](https://spec.graphql.org/October2021/)
+///
+/// Documents using syntax that is not enabled fail with [`MinifyError::UnsupportedSyntax`]
+/// instead of being minified by accident.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Dialect {
+    /// `field?`, `field!` and `field[?]` designators of [client-controlled nullability](https://github.com/graphql/graphql-spec/issues/867)
+    pub client_controlled_nullability: bool,
+    /// variables of fragments and arguments of spreads, e.g. `fragment F($a: Int) on T` and `...F(a: 1)`
+    pub fragment_arguments: bool,
+    /// descriptions of operations and fragments
+    pub operation_descriptions: bool,
+}
+
+impl Dialect {
+    /// the spec without any extensions
+    pub const SPEC: Self = Self {
+        client_controlled_nullability: false,
+        fragment_arguments: false,
+        operation_descriptions: false,
+    };
+
+    /// all supported extensions
+    pub const EXPERIMENTAL: Self = Self {
+        client_controlled_nullability: true,
+        fragment_arguments: true,
+        operation_descriptions: true,
+    };
+
+    /// returns whether the extension is enabled
+    pub const fn allows(&self, extension: Extension) -> bool {
+        match extension {
+            Extension::ClientControlledNullability => self.client_controlled_nullability,
+            Extension::FragmentArguments => self.fragment_arguments,
+            Extension::OperationDescriptions => self.operation_descriptions,
+        }
+    }
+}
+
+/// Syntax extension that can be enabled in [`Dialect`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extension {
+    /// [`Dialect::client_controlled_nullability`]
+    ClientControlledNullability,
+    /// [`Dialect::fragment_arguments`]
+    FragmentArguments,
+    /// [`Dialect::operation_descriptions`]
+    OperationDescriptions,
+}
+
+impl Extension {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Extension::ClientControlledNullability => "client-controlled nullability",
+            Extension::FragmentArguments => "fragment arguments",
+            Extension::OperationDescriptions => "operation descriptions",
+        }
+    }
+}
+
+/// Kind of the definition the tokens belong to
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Context {
+    /// e.g. in a part of a template literal that starts inside a definition
+    Unknown,
+    Executable,
+    TypeSystem,
+}
+
+/// Tokens preceding a possible argument list of a fragment
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pending {
+    None,
+    /// `fragment`
+    Fragment,
+    /// `fragment Name` or `...Name`
+    FragmentName,
+    /// `...`
+    Spread,
+}
+
+/// Detects extensions in the stream of tokens
+///
+/// Only tokens are seen, so the detection relies on the nesting of braces and parentheses
+/// and is skipped where the kind of the definition is unknown.
+pub(crate) struct DialectChecker {
+    dialect: Dialect,
+    context: Context,
+    /// whether a definition keyword was seen since the last top-level `}`
+    in_definition: bool,
+    /// nesting depth of selection sets or bodies of type definitions
+    braces: usize,
+    /// nesting depth of parentheses, braces of object values inside them are not counted
+    parens: usize,
+    pending: Pending,
+    /// top-level string that may be a description of the next definition
+    description: Option<Span>,
+}
+
+impl DialectChecker {
+    pub fn new(dialect: Dialect) -> Self {
+        Self {
+            dialect,
+            context: Context::Unknown,
+            in_definition: false,
+            braces: 0,
+            parens: 0,
+            pending: Pending::None,
+            description: None,
+        }
+    }
+
+    /// checks the token at `span`, returning an error if it uses an extension that is not enabled
+    pub fn check(&mut self, token: &Token, slice: &str, span: Span) -> Option<MinifyError> {
+        let is_top_level = self.braces == 0 && self.parens == 0;
+        let pending = std::mem::replace(&mut self.pending, Pending::None);
+        let description = self.description.take();
+
+        let extension = match token {
+            Token::Question => Some(Extension::ClientControlledNullability),
+            Token::Exclamation | Token::BracketOpen
+                if self.context == Context::Executable && self.braces > 0 && self.parens == 0 =>
+            {
+                Some(Extension::ClientControlledNullability)
+            }
+            Token::ParenOpen if pending == Pending::FragmentName => {
+                Some(Extension::FragmentArguments)
+            }
+            Token::String | Token::BlockStringDelimiter if is_top_level => {
+                self.description = Some(span.clone());
+                None
+            }
+            Token::Ellipsis => {
+                self.pending = Pending::Spread;
+                None
+            }
+            Token::Identifier | Token::Bool => match pending {
+                Pending::Fragment => {
+                    self.pending = Pending::FragmentName;
+                    None
+                }
+                Pending::Spread if slice != "on" => {
+                    self.pending = Pending::FragmentName;
+                    None
+                }
+                _ if is_top_level => self.start_definition(slice, description.as_ref()),
+                _ => None,
+            },
+            Token::BraceOpen if is_top_level && !self.in_definition => {
+                self.in_definition = true;
+                self.context = Context::Executable;
+                description
+                    .as_ref()
+                    .map(|_| Extension::OperationDescriptions)
+            }
+            _ => None,
+        };
+
+        self.track_nesting(token);
+
+        let extension = extension.filter(|extension| !self.dialect.allows(*extension))?;
+        let span = match extension {
+            Extension::OperationDescriptions => description.unwrap_or(span),
+            _ => span,
+        };
+
+        Some(MinifyError::UnsupportedSyntax(extension, span))
+    }
+
+    /// updates the context on a top-level keyword, returning an extension used by the definition
+    fn start_definition(&mut self, keyword: &str, description: Option<&Span>) -> Option<Extension> {
+        self.context = match keyword {
+            "query" | "mutation" | "subscription" | "fragment" => Context::Executable,
+            "schema" | "scalar" | "type" | "interface" | "union" | "enum" | "input"
+            | "directive" | "extend" => Context::TypeSystem,
+            _ => return None,
+        };
+        self.in_definition = true;
+
+        if keyword == "fragment" {
+            self.pending = Pending::Fragment;
+        }
+
+        (self.context == Context::Executable && description.is_some())
+            .then_some(Extension::OperationDescriptions)
+    }
+
+    fn track_nesting(&mut self, token: &Token) {
+        match token {
+            Token::ParenOpen => self.parens += 1,
+            Token::ParenClose => self.parens = self.parens.saturating_sub(1),
+            Token::BraceOpen if self.parens == 0 => self.braces += 1,
+            Token::BraceClose if self.parens == 0 => {
+                self.braces = self.braces.saturating_sub(1);
+
+                if self.braces == 0 {
+                    self.in_definition = false;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use logos::Logos;
+
+    use super::{Dialect, DialectChecker, Extension};
+    use crate::MinifyError;
+    use crate::lexer::{Token, skip_block_string};
+
+    fn check(source: &str, dialect: Dialect) -> Vec<MinifyError> {
+        let mut lexer = Token::lexer(source);
+        let mut checker = DialectChecker::new(dialect);
+        let mut errors = Vec::new();
+
+        while let Some(token) = lexer.next() {
+            let token = token.unwrap();
+
+            if token == Token::BlockStringDelimiter {
+                skip_block_string(&mut lexer).unwrap();
+            }
+
+            errors.extend(checker.check(&token, lexer.slice(), lexer.span()));
+        }
+
+        errors
+    }
+
+    #[test]
+    fn accepts_spec_syntax() {
+        let sources = [
+            r#"query Q($a: [Int!]! = [1]) { a(b: {c: [$a]}) @d(e: "!") { ...F ... on T { f } } }"#,
+            "fragment F on T { a }",
+            r#""""Type""" type T implements A & B { "field" f(a: Int!): [String!]! } union U = A | B"#,
+            "id image { url } ...F",
+        ];
+
+        for source in sources {
+            assert_eq!(check(source, Dialect::SPEC), [], "{source}");
+        }
+    }
+
+    #[test]
+    fn detects_client_controlled_nullability() {
+        let source = "query { a? b! c[!]? d(e: 1)! }";
+
+        assert_eq!(
+            check(source, Dialect::SPEC),
+            [
+                MinifyError::UnsupportedSyntax(Extension::ClientControlledNullability, 9..10),
+                MinifyError::UnsupportedSyntax(Extension::ClientControlledNullability, 12..13),
+                MinifyError::UnsupportedSyntax(Extension::ClientControlledNullability, 15..16),
+                MinifyError::UnsupportedSyntax(Extension::ClientControlledNullability, 16..17),
+                MinifyError::UnsupportedSyntax(Extension::ClientControlledNullability, 18..19),
+                MinifyError::UnsupportedSyntax(Extension::ClientControlledNullability, 27..28),
+            ]
+        );
+        assert_eq!(check(source, Dialect::EXPERIMENTAL), []);
+    }
+
+    #[test]
+    fn detects_fragment_arguments() {
+        let source = "fragment F($a: Int = 1) on T { ...G(a: $a) ...on T { a } }";
+
+        assert_eq!(
+            check(source, Dialect::SPEC),
+            [
+                MinifyError::UnsupportedSyntax(Extension::FragmentArguments, 10..11),
+                MinifyError::UnsupportedSyntax(Extension::FragmentArguments, 35..36),
+            ]
+        );
+        assert_eq!(check(source, Dialect::EXPERIMENTAL), []);
+    }
+
+    #[test]
+    fn detects_operation_descriptions() {
+        let source =
+            r#""Query" query { a } """Fragment""" fragment F on T { a } "Anonymous" { b }"#;
+
+        assert_eq!(
+            check(source, Dialect::SPEC),
+            [
+                MinifyError::UnsupportedSyntax(Extension::OperationDescriptions, 0..7),
+                MinifyError::UnsupportedSyntax(Extension::OperationDescriptions, 20..34),
+                MinifyError::UnsupportedSyntax(Extension::OperationDescriptions, 57..68),
+            ]
+        );
+        assert_eq!(check(source, Dialect::EXPERIMENTAL), []);
+    }
+}
@@ -0,0 +1,135 @@
+//! Removal of directives from the stream of tokens
+
+use logos::Logos;
+
+use crate::MinifyError;
+use crate::edit::Edit;
+use crate::lexer::{Token, skip_block_string};
+
+/// Where the remover is relative to a removed directive
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Outside,
+    /// right after the name of a removed directive, which may be followed by arguments
+    AfterName,
+    /// inside the arguments of a removed directive, with the nesting depth of parentheses
+    InArguments(usize),
+}
+
+/// Finds tokens of directives with the given names, including their arguments
+///
+/// Names in directive definitions (`directive @name on FIELD`) are kept,
+/// since removing them would leave the definition without a name. The `directive` keyword
+/// starts definitions, so it is only recognized outside of braces, parentheses and brackets,
+/// e.g. not as the name of a field in `{ directive @client }`.
+pub(crate) struct DirectiveRemover<'a> {
+    /// names of removed directives without `@`
+    names: &'a [String],
+    state: State,
+    /// nesting depth of braces, parentheses and brackets
+    depth: usize,
+    /// whether the previous token is the `directive` keyword
+    after_keyword: bool,
+}
+
+impl<'a> DirectiveRemover<'a> {
+    pub fn new(names: &'a [String]) -> Self {
+        Self {
+            names,
+            state: State::Outside,
+            depth: 0,
+            after_keyword: false,
+        }
+    }
+
+    /// returns whether the token belongs to a removed directive, the contents of block strings are not passed
+    pub fn is_removed(&mut self, token: &Token, slice: &str) -> bool {
+        let is_keyword = self.depth == 0 && *token == Token::Identifier && slice == "directive";
+        let after_keyword = std::mem::replace(&mut self.after_keyword, is_keyword);
+
+        match token {
+            Token::BraceOpen | Token::ParenOpen | Token::BracketOpen => self.depth += 1,
+            Token::BraceClose | Token::ParenClose | Token::BracketClose => {
+                self.depth = self.depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+
+        match (self.state, token) {
+            (State::AfterName, Token::ParenOpen) => {
+                self.state = State::InArguments(1);
+                true
+            }
+            (State::InArguments(depth), _) => {
+                self.state = match token {
+                    Token::ParenOpen => State::InArguments(depth + 1),
+                    Token::ParenClose if depth == 1 => State::Outside,
+                    Token::ParenClose => State::InArguments(depth - 1),
+                    _ => State::InArguments(depth),
+                };
+                true
+            }
+            (_, Token::Directive)
+                if !after_keyword && self.names.iter().any(|name| name == &slice[1..]) =>
+            {
+                self.state = State::AfterName;
+                true
+            }
+            _ => {
+                self.state = State::Outside;
+                false
+            }
+        }
+    }
+}
+
+/// Returns the deletions of directives with the given `names`, including their arguments,
+/// sorted by their positions.
+///
+/// Unlike [`MinifyOptions::remove_directives`], the rest of the document is kept as written,
+/// so that directives can be removed before the document is split into parts that are minified separately.
+///
+/// # Examples
+///
+/// ```
+/// use graphql_minify::{apply_edits, directive_edits};
+///
+/// let source = r#"{ feed @connection(key: "feed") { id } }"#;
+/// let edits = directive_edits(source, &["connection".into()]).unwrap();
+///
+/// assert_eq!(apply_edits(source, &edits), "{ feed  { id } }");
+/// ```
+///
+/// # Errors
+///
+/// This function will return the first lexing error of the document.
+///
+/// [`MinifyOptions::remove_directives`]: crate::MinifyOptions::remove_directives
+pub fn directive_edits(source: &str, names: &[String]) -> Result<Vec<Edit>, MinifyError> {
+    let mut lexer = Token::lexer(source);
+    let mut directives = DirectiveRemover::new(names);
+    let mut edits = Vec::<Edit>::new();
+    let mut is_previous_removed = false;
+
+    while let Some(token) = lexer.next() {
+        let token = token.map_err(|err| MinifyError::from_lexing_error(err, &lexer))?;
+        let is_removed = directives.is_removed(&token, lexer.slice());
+
+        if token == Token::BlockStringDelimiter {
+            skip_block_string(&mut lexer)
+                .map_err(|err| MinifyError::from_lexing_error(err, &lexer))?;
+        }
+
+        if is_removed {
+            // tokens of a directive are deleted together with the whitespace between them
+            match edits.last_mut() {
+                Some(edit) if is_previous_removed => edit.span.end = lexer.span().end,
+                _ => edits.push(Edit::delete(&lexer.span())),
+            }
+        }
+
+        is_previous_removed = is_removed;
+    }
+
+    Ok(edits)
+}
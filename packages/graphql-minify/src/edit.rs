@@ -0,0 +1,42 @@
+//! Edits of the source that keep the rest of the document as written
+
+use logos::Span;
+
+/// Replacement of the `span` of the source with `text`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub span: Span,
+    /// inserted text, empty for deletions
+    pub text: String,
+}
+
+impl Edit {
+    pub(crate) fn delete(span: &Span) -> Self {
+        Self {
+            span: span.clone(),
+            text: String::new(),
+        }
+    }
+
+    pub(crate) fn insert(position: usize, text: &str) -> Self {
+        Self {
+            span: position..position,
+            text: text.to_owned(),
+        }
+    }
+}
+
+/// Applies sorted non-overlapping `edits` to the `source`.
+pub fn apply_edits(source: &str, edits: &[Edit]) -> String {
+    let mut result = String::with_capacity(source.len());
+    let mut position = 0;
+
+    for edit in edits {
+        result.push_str(&source[position..edit.span.start]);
+        result.push_str(&edit.text);
+        position = edit.span.end;
+    }
+
+    result.push_str(&source[position..]);
+    result
+}
@@ -0,0 +1,223 @@
+use std::fmt;
+
+use logos::{Lexer, Span};
+
+use crate::codes::{self, ErrorCode};
+use crate::dialect::Extension;
+use crate::lexer::{LexingError, Token};
+
+/// An error that occurred while processing a GraphQL document
+///
+/// Spans are byte ranges in the processed document, and [`MinifyError::line_column`]
+/// converts them to human-readable positions when the document is at hand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MinifyError {
+    /// characters that do not form any token, e.g. a string missing its closing quote
+    UnknownToken(Span),
+    /// string containing a line break
+    UnterminatedString(Span),
+    /// escape sequence that is not allowed in strings
+    InvalidEscapeSequence(Span),
+    /// block string without the closing `"""`, the span is the one of the opening `"""`
+    UnterminatedBlockString(Span),
+    /// malformed numeric literal
+    InvalidNumber(Span),
+    /// character that cannot start any token
+    UnexpectedCharacter(char, Span),
+    /// invalid UTF-8 sequence in a document passed as bytes
+    InvalidUtf8(Span),
+    /// syntax of an extension that is not enabled in [`Dialect`](crate::Dialect)
+    UnsupportedSyntax(Extension, Span),
+    /// token that does not fit the grammar, with the description of the expected one,
+    /// the span is empty at the end of the document
+    SyntaxError(&'static str, Span),
+}
+
+impl MinifyError {
+    pub const fn as_str(&self) -> &str {
+        self.error_code().message
+    }
+
+    /// returns the stable code of the error, e.g. `GQLMIN001`
+    pub const fn code(&self) -> &'static str {
+        self.error_code().code
+    }
+
+    /// returns the description of the error from the [`codes`] catalogue
+    pub const fn error_code(&self) -> &'static ErrorCode {
+        match self {
+            MinifyError::UnknownToken(_) => &codes::UNKNOWN_TOKEN,
+            MinifyError::UnterminatedString(_) => &codes::UNTERMINATED_STRING,
+            MinifyError::InvalidEscapeSequence(_) => &codes::INVALID_ESCAPE_SEQUENCE,
+            MinifyError::UnterminatedBlockString(_) => &codes::UNTERMINATED_BLOCK_STRING,
+            MinifyError::InvalidNumber(_) => &codes::INVALID_NUMBER,
+            MinifyError::UnexpectedCharacter(..) => &codes::UNEXPECTED_CHARACTER,
+            MinifyError::InvalidUtf8(_) => &codes::INVALID_UTF8,
+            MinifyError::UnsupportedSyntax(..) => &codes::UNSUPPORTED_SYNTAX,
+            MinifyError::SyntaxError(..) => &codes::SYNTAX_ERROR,
+        }
+    }
+
+    pub const fn span(&self) -> &Span {
+        match self {
+            MinifyError::UnknownToken(span)
+            | MinifyError::UnterminatedString(span)
+            | MinifyError::InvalidEscapeSequence(span)
+            | MinifyError::UnterminatedBlockString(span)
+            | MinifyError::InvalidNumber(span)
+            | MinifyError::UnexpectedCharacter(_, span)
+            | MinifyError::InvalidUtf8(span)
+            | MinifyError::UnsupportedSyntax(_, span)
+            | MinifyError::SyntaxError(_, span) => span,
+        }
+    }
+
+    /// Returns the 1-based line and column of the start of the error in `source`,
+    /// which must be the document the error occurred in.
+    ///
+    /// See [`line_column`] for how lines and columns are counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use graphql_minify::{minify, MinifyAllocator};
+    ///
+    /// let query = "{\n  a %\n}";
+    /// let err = minify(query, &mut MinifyAllocator::default()).unwrap_err();
+    ///
+    /// assert_eq!(err.line_column(query), (2, 5));
+    /// ```
+    pub fn line_column(&self, source: &str) -> (usize, usize) {
+        line_column(source, self.span().start)
+    }
+
+    pub(crate) fn from_lexing_error(err: LexingError, lexer: &Lexer<Token>) -> Self {
+        match err {
+            LexingError::UnknownToken => {
+                let mut chars = lexer.slice().chars();
+
+                match (chars.next(), chars.next()) {
+                    // a lone `"` is a string missing its closing quote rather than an unexpected character
+                    (Some(c), None) if c != '"' => {
+                        MinifyError::UnexpectedCharacter(c, lexer.span())
+                    }
+                    _ => MinifyError::UnknownToken(lexer.span()),
+                }
+            }
+            LexingError::UnterminatedString(span) => MinifyError::UnterminatedString(span),
+            LexingError::UnterminatedBlockString(span) => {
+                MinifyError::UnterminatedBlockString(span)
+            }
+            LexingError::InvalidNumber => MinifyError::InvalidNumber(lexer.span()),
+            LexingError::InvalidEscapeSequence(span) => MinifyError::InvalidEscapeSequence(span),
+        }
+    }
+}
+
+impl fmt::Display for MinifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Span { start, end } = self.span();
+
+        match self {
+            MinifyError::UnexpectedCharacter(c, _) => {
+                write!(
+                    f,
+                    "{} `{}` at {start}..{end}",
+                    self.as_str(),
+                    c.escape_debug()
+                )
+            }
+            MinifyError::UnsupportedSyntax(extension, _) => {
+                write!(
+                    f,
+                    "{} of {} at {start}..{end}",
+                    self.as_str(),
+                    extension.name()
+                )
+            }
+            MinifyError::SyntaxError(expected, _) => {
+                write!(
+                    f,
+                    "{}, expected {expected} at {start}..{end}",
+                    self.as_str()
+                )
+            }
+            _ => write!(f, "{} at {start}..{end}", self.as_str()),
+        }
+    }
+}
+
+impl std::error::Error for MinifyError {}
+
+/// Returns the 1-based line and column of the byte `offset` in `source`.
+///
+/// Columns are counted in characters, and `\r\n`, `\r` and `\n` are all treated as line terminators.
+/// Offsets past the end of `source` are clamped to it.
+///
+/// # Examples
+///
+/// ```
+/// use graphql_minify::line_column;
+///
+/// assert_eq!(line_column("{\r\n  a\r  b }", 9), (3, 3));
+/// ```
+pub fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let start = offset.min(source.len());
+    let before = source.get(..start).unwrap_or_default();
+
+    let mut line = 1;
+    let mut line_start = 0;
+    let mut chars = before.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if c == '\r' && chars.peek().is_some_and(|&(_, next)| next == '\n') {
+            continue;
+        }
+
+        if c == '\n' || c == '\r' {
+            line += 1;
+            line_start = i + 1;
+        }
+    }
+
+    (line, before[line_start..].chars().count() + 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::MinifyError;
+    use crate::dialect::Extension;
+
+    #[test]
+    fn displays_errors() {
+        assert_eq!(
+            MinifyError::UnterminatedString(4..9).to_string(),
+            "unterminated string at 4..9"
+        );
+        assert_eq!(
+            MinifyError::UnexpectedCharacter('\'', 2..3).to_string(),
+            "unexpected character `\\'` at 2..3"
+        );
+        assert_eq!(
+            MinifyError::UnsupportedSyntax(Extension::FragmentArguments, 10..11).to_string(),
+            "unsupported syntax of fragment arguments at 10..11"
+        );
+        assert_eq!(
+            MinifyError::SyntaxError("a selection set", 7..7).to_string(),
+            "syntax error, expected a selection set at 7..7"
+        );
+    }
+
+    #[test]
+    fn computes_line_and_column() {
+        let source = "a\r\nb\rc\n  é %";
+
+        assert_eq!(MinifyError::UnknownToken(0..1).line_column(source), (1, 1));
+        assert_eq!(MinifyError::UnknownToken(3..4).line_column(source), (2, 1));
+        assert_eq!(MinifyError::UnknownToken(5..6).line_column(source), (3, 1));
+        assert_eq!(
+            MinifyError::UnknownToken(12..13).line_column(source),
+            (4, 5)
+        );
+    }
+}
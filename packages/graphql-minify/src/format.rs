@@ -0,0 +1,445 @@
+//! GraphQL pretty-printer built on top of the minifier's [`Token`] lexer
+//!
+//! Since only tokens are available, the document structure is guessed from the punctuators:
+//! - `{` opens a block (selection set, fields definition, enum values, etc.),
+//!   whose items are printed on separate lines, unless it is an object value
+//! - `(` and `[` open inline lists, whose items are separated by `, `
+//! - top-level definitions are separated by an empty line
+//!
+//! Documents that do not start with a definition (e.g. a bare list of fields)
+//! are formatted as the contents of a selection set.
+
+use logos::Logos;
+
+use crate::lexer::{Token, parse_block_string};
+use crate::{MinifyAllocator, MinifyError, needs_space};
+
+const INDENT: &str = "  ";
+
+/// [Definition] keywords
+///
+/// [Definition]: https://spec.graphql.org/October2021/#Definition
+const DEFINITION_KEYWORDS: &[&str] = &[
+    "query",
+    "mutation",
+    "subscription",
+    "fragment",
+    "schema",
+    "scalar",
+    "type",
+    "interface",
+    "union",
+    "enum",
+    "input",
+    "directive",
+    "extend",
+];
+
+/// Pretty-prints a GraphQL document, indenting nested blocks and dropping comments.
+///
+/// Like [`minify`](crate::minify), it does not check the validity of the document
+/// and works with partial documents too.
+///
+/// # Examples
+///
+/// ```
+/// use graphql_minify::{format, MinifyAllocator};
+///
+/// let original = "query SomeQuery($foo: String!, $bar: String) { someField(foo: $foo) { a b { c } } }";
+/// let mut alloc = MinifyAllocator::default();
+/// let formatted = format(original, &mut alloc).unwrap();
+///
+/// assert_eq!(
+///     formatted,
+///     "query SomeQuery($foo: String!, $bar: String) {\n  someField(foo: $foo) {\n    a\n    b {\n      c\n    }\n  }\n}"
+/// );
+/// ```
+///
+/// # Errors
+///
+/// This function will return an error if the lexing process encounters an unexpected character.
+pub fn format<T: AsRef<str>>(value: T, alloc: &mut MinifyAllocator) -> Result<String, MinifyError> {
+    let value = value.as_ref();
+    let mut lexer = Token::lexer(value);
+    let mut formatter = Formatter::with_capacity(value.len());
+
+    while let Some(token) = lexer.next() {
+        let token = match token {
+            Ok(token) => token,
+            Err(e) => return Err(MinifyError::from_lexing_error(e, &lexer)),
+        };
+
+        match token {
+            Token::BlockStringDelimiter => {
+                alloc.block_string.reset();
+                let block_string = parse_block_string(&mut lexer, &alloc.block_string)
+                    .map_err(|e| MinifyError::from_lexing_error(e, &lexer))?;
+                formatter.separate(token, "");
+                formatter.push_block_string(block_string.as_ref());
+            }
+            _ => formatter.push(token, lexer.slice()),
+        }
+    }
+
+    alloc.block_string.reset();
+    Ok(formatter.result)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Frame {
+    /// top level of a document consisting of definitions
+    Document,
+    /// block whose items are printed on separate lines
+    Block,
+    /// arguments, lists and object values, whose items are printed on the same line
+    Inline,
+}
+
+struct Formatter<'a> {
+    result: String,
+    frames: Vec<Frame>,
+    top_level: Option<Frame>,
+    last: Option<(Token, &'a str)>,
+}
+
+impl<'a> Formatter<'a> {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            // formatted documents are usually slightly larger than the original ones
+            result: String::with_capacity(capacity + capacity / 4),
+            frames: Vec::new(),
+            top_level: None,
+            last: None,
+        }
+    }
+
+    fn push(&mut self, token: Token, slice: &'a str) {
+        match token {
+            Token::BraceClose => {
+                let frame = self.frames.pop();
+                let is_empty_block = matches!(self.last, Some((Token::BraceOpen, _)));
+
+                if frame == Some(Frame::Block) && !is_empty_block {
+                    self.new_line();
+                }
+            }
+            Token::ParenClose | Token::BracketClose => {
+                self.frames.pop();
+            }
+            _ => self.separate(token, slice),
+        }
+
+        self.result.push_str(slice);
+
+        match token {
+            Token::BraceOpen => {
+                let is_inline = self.current_frame() == Frame::Inline
+                    || matches!(self.last, Some((Token::Colon | Token::Equals, _)));
+
+                self.frames.push(if is_inline {
+                    Frame::Inline
+                } else {
+                    Frame::Block
+                });
+            }
+            Token::ParenOpen | Token::BracketOpen => self.frames.push(Frame::Inline),
+            _ => {}
+        }
+
+        self.last = Some((token, slice));
+    }
+
+    fn push_block_string(&mut self, block_string: &str) {
+        const TRIPLE_QUOTES: &str = r#"""""#;
+
+        let content = &block_string[TRIPLE_QUOTES.len()..block_string.len() - TRIPLE_QUOTES.len()];
+
+        if self.current_frame() == Frame::Inline || !content.contains('\n') {
+            self.result.push_str(block_string);
+        } else {
+            // lines are dedented, so the indentation of the block can be safely added to them,
+            // and the first line is moved to a separate line so that it's dedented the same way
+            let content = content.strip_prefix('\n').unwrap_or(content);
+            let content = content.strip_suffix('\n').unwrap_or(content);
+
+            self.result.push_str(TRIPLE_QUOTES);
+
+            for line in content.split('\n') {
+                if line.is_empty() {
+                    self.result.push('\n');
+                } else {
+                    self.new_line();
+                    self.result.push_str(line);
+                }
+            }
+
+            self.new_line();
+            self.result.push_str(TRIPLE_QUOTES);
+        }
+
+        self.last = Some((Token::BlockStringDelimiter, ""));
+    }
+
+    /// pushes the separator required between the last token and the given one
+    fn separate(&mut self, token: Token, slice: &str) {
+        let Some((last, last_slice)) = self.last else {
+            self.top_level = Some(if starts_definition(token, slice) {
+                Frame::Document
+            } else {
+                Frame::Block
+            });
+            return;
+        };
+
+        match self.current_frame() {
+            Frame::Document => {
+                if is_description(last) && token == Token::Identifier {
+                    self.new_line();
+                } else if last == Token::BraceClose
+                    || (token != Token::BraceOpen
+                        && starts_definition(token, slice)
+                        && last_slice != "extend")
+                {
+                    self.result.push('\n');
+                    self.new_line();
+                } else {
+                    self.push_inline_separator(last, last_slice, token, slice);
+                }
+            }
+            Frame::Block => {
+                if last == Token::BraceOpen || starts_block_item(last, last_slice, token) {
+                    self.new_line();
+                } else {
+                    self.push_inline_separator(last, last_slice, token, slice);
+                }
+            }
+            Frame::Inline => {
+                if starts_inline_item(last, token) {
+                    self.result.push_str(", ");
+                } else {
+                    self.push_inline_separator(last, last_slice, token, slice);
+                }
+            }
+        }
+    }
+
+    fn push_inline_separator(&mut self, last: Token, last_slice: &str, token: Token, slice: &str) {
+        let needs_space = match (last, token) {
+            (
+                _,
+                Token::Colon
+                | Token::Exclamation
+                | Token::ParenOpen
+                | Token::ParenClose
+                | Token::BracketClose,
+            )
+            | (Token::ParenOpen | Token::BracketOpen | Token::BraceOpen, _) => false,
+            (Token::Ellipsis, _) => token == Token::Directive || slice == "on",
+            (Token::Colon | Token::Equals | Token::Pipe | Token::Ampersand, _)
+            | (Token::ParenClose | Token::BracketClose, Token::Identifier)
+            | (_, Token::Equals | Token::Pipe | Token::Ampersand | Token::BraceOpen)
+            | (_, Token::Directive) => true,
+            _ => last_slice == "on" || needs_space(&token, Some(&last)),
+        };
+
+        if needs_space {
+            self.result.push(' ');
+        }
+    }
+
+    fn new_line(&mut self) {
+        self.result.push('\n');
+
+        for _ in 0..self.depth() {
+            self.result.push_str(INDENT);
+        }
+    }
+
+    fn current_frame(&self) -> Frame {
+        self.frames
+            .last()
+            .copied()
+            .or(self.top_level)
+            .unwrap_or(Frame::Block)
+    }
+
+    fn depth(&self) -> usize {
+        self.frames.iter().filter(|&&f| f == Frame::Block).count()
+    }
+}
+
+fn starts_definition(token: Token, slice: &str) -> bool {
+    match token {
+        Token::BraceOpen | Token::String | Token::BlockStringDelimiter => true,
+        Token::Identifier => DEFINITION_KEYWORDS.contains(&slice),
+        _ => false,
+    }
+}
+
+fn is_description(token: Token) -> bool {
+    matches!(token, Token::String | Token::BlockStringDelimiter)
+}
+
+/// whether the token can end an item of a block or an inline list
+fn ends_item(token: Token) -> bool {
+    matches!(
+        token,
+        Token::Identifier
+            | Token::Variable
+            | Token::Directive
+            | Token::Int
+            | Token::Float
+            | Token::Bool
+            | Token::String
+            | Token::BlockStringDelimiter
+            | Token::Exclamation
+            | Token::ParenClose
+            | Token::BracketClose
+            | Token::BraceClose
+    )
+}
+
+fn starts_block_item(last: Token, last_slice: &str, token: Token) -> bool {
+    matches!(
+        token,
+        Token::Identifier | Token::Ellipsis | Token::String | Token::BlockStringDelimiter
+    ) && ends_item(last)
+        // type condition of an inline fragment
+        && last_slice != "on"
+}
+
+fn starts_inline_item(last: Token, token: Token) -> bool {
+    matches!(
+        token,
+        Token::Identifier
+            | Token::Variable
+            | Token::Int
+            | Token::Float
+            | Token::Bool
+            | Token::String
+            | Token::BlockStringDelimiter
+            | Token::BracketOpen
+            | Token::BraceOpen
+    ) && ends_item(last)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use crate::MinifyError;
+
+    fn format<T: AsRef<str>>(value: T) -> Result<String, MinifyError> {
+        super::format(value, &mut crate::MinifyAllocator::default())
+    }
+
+    #[test]
+    fn formats_executable_document() {
+        let query = r#"
+            query SomeQuery($foo: String! = "foo", $bar: [Int!] = [1 2]) @live {
+                someField(foo: $foo, bar: $bar, obj: {a: 1 b: {c: true}}) { a b { c d } ...F ... on T { e } }
+            }
+            fragment F on T { x: y @include(if: $foo) }
+        "#;
+
+        let expected = indoc! {r#"
+            query SomeQuery($foo: String! = "foo", $bar: [Int!] = [1, 2]) @live {
+              someField(foo: $foo, bar: $bar, obj: {a: 1, b: {c: true}}) {
+                a
+                b {
+                  c
+                  d
+                }
+                ...F
+                ... on T {
+                  e
+                }
+              }
+            }
+
+            fragment F on T {
+              x: y @include(if: $foo)
+            }"#};
+
+        assert_eq!(format(query).unwrap(), expected);
+    }
+
+    #[test]
+    fn formats_schema_document() {
+        let schema = r#"
+            """Type description""" type Foo implements Bar & Baz {
+                """
+                Field description
+
+                second line
+                """
+                bar(a: Int = 1, b: [String!]! = ["x", "y"]): String @deprecated(reason: "no") baz: Int }
+            enum E { A B } union U = A | B scalar S
+            "Directive" directive @d(a: Int) on FIELD | QUERY
+            extend type Foo { qux: Int }
+        "#;
+
+        let expected = indoc! {r#"
+            """Type description"""
+            type Foo implements Bar & Baz {
+              """
+              Field description
+
+              second line
+              """
+              bar(a: Int = 1, b: [String!]! = ["x", "y"]): String @deprecated(reason: "no")
+              baz: Int
+            }
+
+            enum E {
+              A
+              B
+            }
+
+            union U = A | B
+
+            scalar S
+
+            "Directive"
+            directive @d(a: Int) on FIELD | QUERY
+
+            extend type Foo {
+              qux: Int
+            }"#};
+
+        assert_eq!(format(schema).unwrap(), expected);
+    }
+
+    #[test]
+    fn formats_selection_set_contents() {
+        let fields = "id name # comment\n image { id url(size: 1) @skip(if: $x) } ...F";
+        let expected = "id\nname\nimage {\n  id\n  url(size: 1) @skip(if: $x)\n}\n...F";
+
+        assert_eq!(format(fields).unwrap(), expected);
+    }
+
+    #[test]
+    fn formats_empty_blocks_and_documents() {
+        assert_eq!(format("").unwrap(), "");
+        assert_eq!(format(" ,\n").unwrap(), "");
+        assert_eq!(format("{ }").unwrap(), "{}");
+    }
+
+    #[test]
+    fn formats_unbalanced_documents() {
+        assert_eq!(format("a { b").unwrap(), "a {\n  b");
+        assert_eq!(format("b } c").unwrap(), "b}\nc");
+    }
+
+    #[test]
+    fn errs_on_invalid_token() {
+        assert!(matches!(
+            format("{ foo(arg: \"\n\" }"),
+            Err(MinifyError::UnterminatedString(_))
+        ));
+        assert_eq!(
+            format("{ foo } \"\"\" bar"),
+            Err(MinifyError::UnterminatedBlockString(8..11))
+        );
+    }
+}
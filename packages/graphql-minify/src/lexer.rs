@@ -1,9 +1,10 @@
 use bumpalo::Bump;
 use bumpalo::collections::String as BumpaloString;
-use logos::{Lexer, Logos, Span};
+use logos::{FilterResult, Lexer, Logos, Span};
 
 use super::block_string::{BlockStringToken, dedent_block_lines_mut, print_block_string};
 use crate::block_string::{BlockStringLines, PrintedBlockString};
+use crate::options::Whitespace;
 
 #[derive(Debug, PartialEq, Clone, Default)]
 /// An enumeration of errors that can occur during the lexing process.
@@ -12,11 +13,18 @@ pub enum LexingError {
     UnknownToken,
     /// First value is the index of the first character of the unterminated string
     UnterminatedString(Span),
+    /// Span of the opening delimiter of the block string
+    UnterminatedBlockString(Span),
+    /// Numeric literal with leading zeros or followed by a digit, `.` or a name
+    InvalidNumber,
+    /// Span of the first invalid escape sequence of the string
+    InvalidEscapeSequence(Span),
 }
 
-#[derive(Logos, Debug, PartialEq)]
-#[logos(skip r"[\s,]+")]
+#[derive(Logos, Debug, PartialEq, Clone, Copy)]
+#[logos(skip r"[\t\n\r ,]+")]
 #[logos(error = LexingError)]
+#[logos(extras = Whitespace)]
 pub(crate) enum Token {
     #[token("{")]
     BraceOpen,
@@ -60,13 +68,13 @@ pub(crate) enum Token {
     #[token(r#"""""#)]
     BlockStringDelimiter,
 
-    #[regex(r#""([^"\\]+|\\.)*""#, validate_string)]
+    #[token("\"", lex_string)]
     String,
 
-    #[regex("-?[0-9]+")]
+    #[regex("-?[0-9]+", validate_number)]
     Int,
 
-    #[regex("-?[0-9]+\\.[0-9]+(e-?[0-9]+)?")]
+    #[regex("-?[0-9]+(\\.[0-9]+|(\\.[0-9]+)?[eE][+-]?[0-9]+)", validate_number)]
     Float,
 
     #[regex("true|false")]
@@ -83,12 +91,21 @@ pub(crate) enum Token {
 
     #[regex(r"#[^\r\n]*", logos::skip)]
     Comment,
+
+    #[regex(r"[\s&&[^\t\n\r ]]", skip_whitespace)]
+    UnicodeWhitespace,
+
+    #[token("\u{FEFF}", skip_byte_order_mark)]
+    ByteOrderMark,
 }
 
+/// parses the block string whose opening delimiter has just been lexed
+///
+/// The lexer is not advanced past the opening delimiter if the block string is not closed
 pub(crate) fn parse_block_string<'bump>(
     lexer: &mut Lexer<Token>,
     alloc: &'bump Bump,
-) -> PrintedBlockString<'bump> {
+) -> Result<PrintedBlockString<'bump>, LexingError> {
     let remainder = lexer.remainder();
 
     let mut block_string_lines = BlockStringLines::with_capacity_in(5, alloc);
@@ -97,6 +114,7 @@ pub(crate) fn parse_block_string<'bump>(
         let mut block_lexer = BlockStringToken::lexer(remainder);
         let mut current_line = BumpaloString::new_in(alloc);
         let mut max_line_length = 0;
+        let mut is_terminated = false;
 
         while let Some(Ok(token)) = block_lexer.next() {
             match token {
@@ -111,10 +129,17 @@ pub(crate) fn parse_block_string<'bump>(
                 | BlockStringToken::EscapedTripleQuote => {
                     current_line.push_str(block_lexer.slice());
                 }
-                BlockStringToken::TripleQuote => break,
+                BlockStringToken::TripleQuote => {
+                    is_terminated = true;
+                    break;
+                }
             }
         }
 
+        if !is_terminated {
+            return Err(LexingError::UnterminatedBlockString(lexer.span()));
+        }
+
         if !current_line.is_empty() {
             block_string_lines.push(current_line);
         }
@@ -123,7 +148,93 @@ pub(crate) fn parse_block_string<'bump>(
     }
 
     dedent_block_lines_mut(&mut block_string_lines);
-    print_block_string(&block_string_lines, alloc)
+    Ok(print_block_string(&block_string_lines, alloc))
+}
+
+/// skips the rest of the block string whose opening delimiter has just been lexed
+///
+/// The lexer is not advanced past the opening delimiter if the block string is not closed
+pub(crate) fn skip_block_string(lexer: &mut Lexer<Token>) -> Result<(), LexingError> {
+    let remainder = lexer.remainder();
+    let mut block_lexer = BlockStringToken::lexer(remainder);
+
+    while let Some(Ok(token)) = block_lexer.next() {
+        if token == BlockStringToken::TripleQuote {
+            lexer.bump(remainder.len() - block_lexer.remainder().len());
+            return Ok(());
+        }
+    }
+
+    Err(LexingError::UnterminatedBlockString(lexer.span()))
+}
+
+/// skips a whitespace character that is not a GraphQL ignored token, unless whitespace is strict
+fn skip_whitespace(lexer: &Lexer<Token>) -> FilterResult<(), LexingError> {
+    if lexer.extras == Whitespace::Lenient {
+        FilterResult::Skip
+    } else {
+        FilterResult::Error(LexingError::UnknownToken)
+    }
+}
+
+/// skips the byte order mark at the start of the document in both modes, it is unexpected anywhere else
+fn skip_byte_order_mark(lexer: &Lexer<Token>) -> FilterResult<(), LexingError> {
+    if lexer.span().start == 0 {
+        FilterResult::Skip
+    } else {
+        FilterResult::Error(LexingError::UnknownToken)
+    }
+}
+
+/// checks that the number has no leading zeros
+/// and is not followed by a digit, `.` or a name start, as required by the spec
+fn validate_number(lexer: &mut Lexer<Token>) -> Result<(), LexingError> {
+    let is_continuation = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '.';
+
+    let digits = lexer.slice().trim_start_matches('-').as_bytes();
+    let has_leading_zero = digits.len() > 1 && digits[0] == b'0' && digits[1].is_ascii_digit();
+
+    if !has_leading_zero && !lexer.remainder().starts_with(is_continuation) {
+        return Ok(());
+    }
+
+    // the whole malformed literal is reported, so that it is not split into several tokens
+    let remainder = lexer.remainder();
+    lexer.bump(
+        remainder
+            .find(|c| !is_continuation(c))
+            .unwrap_or(remainder.len()),
+    );
+
+    Err(LexingError::InvalidNumber)
+}
+
+/// scans the string whose opening quote has just been lexed up to its closing quote
+///
+/// The string is scanned iteratively rather than by a regex, which overflows the stack on long strings
+/// ([logos#384](https://github.com/maciejhirsz/logos/issues/384)). A string without the closing quote
+/// is an unknown token spanning to the end of the document
+fn lex_string(lexer: &mut Lexer<Token>) -> Result<(), LexingError> {
+    let remainder = lexer.remainder().as_bytes();
+    let mut pos = 0;
+
+    loop {
+        let Some(offset) = memchr::memchr2(b'"', b'\\', &remainder[pos..]) else {
+            lexer.bump(remainder.len());
+            return Err(LexingError::UnknownToken);
+        };
+        let i = pos + offset;
+
+        if remainder[i] == b'"' {
+            lexer.bump(i + 1);
+            break;
+        }
+
+        // skip the escaped character, continuation bytes of a multibyte one are never matched by `memchr2`
+        pos = (i + 2).min(remainder.len());
+    }
+
+    validate_string(lexer)
 }
 
 #[inline]
@@ -131,9 +242,98 @@ fn validate_string(lexer: &Lexer<Token>) -> Result<(), LexingError> {
     let str = lexer.slice().as_bytes();
 
     if have_newline(str) {
-        Err(LexingError::UnterminatedString(lexer.span()))
-    } else {
-        Ok(())
+        return Err(LexingError::UnterminatedString(lexer.span()));
+    }
+
+    match invalid_escape(lexer.slice()) {
+        Some(escape) => {
+            let start = lexer.span().start;
+            Err(LexingError::InvalidEscapeSequence(
+                start + escape.start..start + escape.end,
+            ))
+        }
+        None => Ok(()),
+    }
+}
+
+/// returns the range of the first escape sequence in `str` that is not allowed by the spec
+fn invalid_escape(str: &str) -> Option<Span> {
+    let mut pos = 0;
+
+    while let Some(offset) = memchr::memchr(b'\\', &str.as_bytes()[pos..]) {
+        let start = pos + offset;
+
+        match escape_len(&str[start + 1..]) {
+            Ok(len) => pos = start + 1 + len,
+            Err(len) => return Some(start..start + 1 + len),
+        }
+    }
+
+    None
+}
+
+/// returns the length of the escape sequence following `\`,
+/// or the length of its invalid part if it is not allowed
+fn escape_len(escape: &str) -> Result<usize, usize> {
+    match escape.chars().next() {
+        Some('"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't') => Ok(1),
+        Some('u') if escape[1..].starts_with('{') => braced_unicode_escape_len(escape),
+        Some('u') => unicode_escape_len(escape),
+        Some(c) => Err(c.len_utf8()),
+        None => Err(0),
+    }
+}
+
+/// returns the length of `u{X…}` escaping a Unicode scalar value
+fn braced_unicode_escape_len(escape: &str) -> Result<usize, usize> {
+    const PREFIX_LEN: usize = "u{".len();
+
+    let digits = escape[PREFIX_LEN..]
+        .bytes()
+        .take_while(u8::is_ascii_hexdigit)
+        .count();
+    let len = PREFIX_LEN + digits;
+
+    if digits == 0 || !escape[len..].starts_with('}') {
+        return Err(len);
+    }
+
+    u32::from_str_radix(&escape[PREFIX_LEN..len], 16)
+        .ok()
+        .and_then(char::from_u32)
+        .map_or(Err(len + 1), |_| Ok(len + 1))
+}
+
+/// returns the length of `uXXXX` escaping a Unicode scalar value or of `uXXXX\uXXXX` surrogate pair
+fn unicode_escape_len(escape: &str) -> Result<usize, usize> {
+    const LEN: usize = "uXXXX".len();
+    const PAIR_LEN: usize = "uXXXX\\uXXXX".len();
+
+    let code_unit = |escape: &str| {
+        let digits = escape.get(1..LEN)?;
+        digits
+            .bytes()
+            .all(|b| b.is_ascii_hexdigit())
+            .then(|| u32::from_str_radix(digits, 16).ok())
+            .flatten()
+    };
+
+    let Some(code) = code_unit(escape) else {
+        let digits = escape[1..]
+            .bytes()
+            .take(4)
+            .take_while(u8::is_ascii_hexdigit)
+            .count();
+        return Err(1 + digits);
+    };
+
+    match code {
+        0xD800..0xDC00 => match escape[LEN..].strip_prefix('\\').and_then(code_unit) {
+            Some(0xDC00..0xE000) => Ok(PAIR_LEN),
+            _ => Err(LEN),
+        },
+        0xDC00..0xE000 => Err(LEN),
+        _ => Ok(LEN),
     }
 }
 
@@ -153,3 +353,131 @@ fn have_newline(text: &[u8]) -> bool {
 fn have_newline_naive(text: &[u8]) -> bool {
     text.iter().any(|&b| b == b'\n' || b == b'\r')
 }
+
+#[cfg(test)]
+mod test {
+    use logos::{Logos, Span};
+
+    use super::{LexingError, Token};
+
+    fn lex_one(source: &str) -> (Result<Token, LexingError>, Span) {
+        let mut lexer = Token::lexer(source);
+        let token = lexer.next().expect("no token");
+
+        (token, lexer.span())
+    }
+
+    #[test]
+    fn lexes_numbers() {
+        for (source, token) in [
+            ("4", Token::Int),
+            ("4.123", Token::Float),
+            ("-4", Token::Int),
+            ("9", Token::Int),
+            ("0", Token::Int),
+            ("-4.123", Token::Float),
+            ("0.123", Token::Float),
+            ("123e4", Token::Float),
+            ("123E4", Token::Float),
+            ("123e-4", Token::Float),
+            ("123e+4", Token::Float),
+            ("-1.123e4", Token::Float),
+            ("-1.123E4", Token::Float),
+            ("-1.123e-4", Token::Float),
+            ("-1.123e+4", Token::Float),
+            ("-1.123e4567", Token::Float),
+            ("-0.0E-2", Token::Float),
+        ] {
+            assert_eq!(lex_one(source), (Ok(token), 0..source.len()), "{source}");
+        }
+    }
+
+    #[test]
+    fn reports_invalid_numbers() {
+        for source in [
+            "00", "01", "01.23", "007", "1.", "1e", "1.e1", "1.A", "1.0e", "1.0eA", "1.2e3e",
+            "1.2e3.4", "1.23.4", "0xF1", "0b10", "123abc", "1_234", "1.23f", "1.234_5",
+        ] {
+            assert_eq!(
+                lex_one(source),
+                (Err(LexingError::InvalidNumber), 0..source.len()),
+                "{source}"
+            );
+        }
+
+        // the malformed part ends at the first character that cannot continue it
+        assert_eq!(lex_one("1.0e\""), (Err(LexingError::InvalidNumber), 0..4));
+    }
+
+    #[test]
+    fn lexes_escape_sequences() {
+        for source in [
+            r#""\"\\\/\b\f\n\r\t""#,
+            r#""unicode \u1234\u5678\u90AB\uCDEF""#,
+            r#""\u{1234}\u{0}\u{10FFFF}\u{0000000000000001F600}""#,
+            r#""pair \uD83D\uDE00""#,
+        ] {
+            assert_eq!(
+                lex_one(source),
+                (Ok(Token::String), 0..source.len()),
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn lexes_long_strings() {
+        let source = format!("\"{}\"", "\\\\\\\"a".repeat(1 << 16));
+        assert_eq!(lex_one(&source), (Ok(Token::String), 0..source.len()));
+
+        let unterminated = format!("\"{}", "\\\"".repeat(1 << 16));
+        assert_eq!(
+            lex_one(&unterminated),
+            (Err(LexingError::UnknownToken), 0..unterminated.len())
+        );
+    }
+
+    #[test]
+    fn reports_invalid_escape_sequences() {
+        for (source, span) in [
+            (r#""bad \z esc""#, 5..7),
+            (r#""bad \x esc""#, 5..7),
+            (r#""bad \u1 esc""#, 5..8),
+            (r#""bad \u0XX1 esc""#, 5..8),
+            (r#""bad \uXXXX esc""#, 5..7),
+            (r#""bad \uFXXX esc""#, 5..8),
+            (r#""bad \uXXXF esc""#, 5..7),
+            (r#""bad \u{} esc""#, 5..8),
+            (r#""bad \u{FXXX} esc""#, 5..9),
+            (r#""bad \u{110000} esc""#, 5..15),
+            (r#""bad \u{D800} esc""#, 5..13),
+            (r#""bad \u{1F600 esc""#, 5..13),
+            (r#""bad \uD83D esc""#, 5..11),
+            (r#""bad \uD83D\u0061 esc""#, 5..11),
+            (r#""bad \uDE00\uD83D esc""#, 5..11),
+        ] {
+            assert_eq!(
+                lex_one(source),
+                (
+                    Err(LexingError::InvalidEscapeSequence(span.clone())),
+                    0..source.len()
+                ),
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn does_not_lex_signs_and_dots_as_numbers() {
+        for source in ["+1", ".123", "-A"] {
+            assert_eq!(
+                lex_one(source),
+                (Err(LexingError::UnknownToken), 0..1),
+                "{source}"
+            );
+        }
+
+        // not a name start, so the number ends before it
+        assert_eq!(lex_one("1\u{df}"), (Ok(Token::Int), 0..1));
+    }
+}
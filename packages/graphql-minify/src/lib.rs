@@ -1,33 +1,43 @@
+pub mod ast;
 mod block_string;
+pub mod codes;
+mod definitions;
+mod descriptions;
+mod dialect;
+mod directives;
+mod edit;
+mod error;
+mod format;
 mod lexer;
+mod lint;
 mod minify_alloc;
-
-use logos::{Logos, Span};
-
-use crate::lexer::{LexingError, Token, parse_block_string};
+mod options;
+mod parser;
+mod schema;
+mod transform;
+mod usages;
+mod validation;
+
+pub use bumpalo::Bump;
+use logos::{Lexer, Logos};
+
+pub use crate::definitions::{Definition, DefinitionKind, definitions};
+use crate::descriptions::DescriptionDetector;
+use crate::dialect::DialectChecker;
+pub use crate::dialect::{Dialect, Extension};
+use crate::directives::DirectiveRemover;
+pub use crate::directives::directive_edits;
+pub use crate::edit::{Edit, apply_edits};
+pub use crate::error::{MinifyError, line_column};
+pub use crate::format::format;
+use crate::lexer::{Token, parse_block_string};
+pub use crate::lint::{Lint, LintRule, Linter};
 pub use crate::minify_alloc::MinifyAllocator;
-
-#[derive(Debug)]
-#[cfg_attr(test, derive(PartialEq, Eq))]
-pub enum MinifyError {
-    UnknownToken(Span),
-    UnterminatedString(Span),
-}
-
-impl MinifyError {
-    pub const fn as_str(&self) -> &str {
-        match self {
-            MinifyError::UnknownToken(_) => "unknown token",
-            MinifyError::UnterminatedString(_) => "unterminated string",
-        }
-    }
-
-    pub const fn span(&self) -> &Span {
-        match self {
-            MinifyError::UnknownToken(span) | MinifyError::UnterminatedString(span) => span,
-        }
-    }
-}
+pub use crate::options::{Minified, MinifyOptions, Whitespace};
+pub use crate::parser::{ParseOptions, parse, parse_selections};
+pub use crate::schema::Schema;
+pub use crate::transform::{TransformOptions, transform, transform_edits};
+pub use crate::validation::{DeprecatedKind, Deprecation, ValidationError};
 
 /// Strips characters that are not significant to the validity or execution of a GraphQL document.
 ///
@@ -59,6 +69,9 @@ impl MinifyError {
 ///
 /// This function will return an error if the lexing process encounters an unexpected character.
 ///
+/// Syntax of all extensions of [`Dialect::EXPERIMENTAL`] is accepted, use [`minify_with_options`]
+/// to reject syntax beyond the spec.
+///
 /// # Panics
 ///
 /// This function does not panic.
@@ -67,37 +80,288 @@ impl MinifyError {
 ///
 /// This function does not use any unsafe code.
 pub fn minify<T: AsRef<str>>(value: T, alloc: &mut MinifyAllocator) -> Result<String, MinifyError> {
+    minify_impl(value.as_ref(), alloc, OnError::Stop, &permissive_options())
+        .map(|minified| minified.output)
+        .map_err(|mut errors| errors.swap_remove(0))
+}
+
+/// Same as [`minify`], but takes a document as bytes, checking that it is valid UTF-8.
+///
+/// # Examples
+///
+/// ```
+/// use graphql_minify::{minify_bytes, MinifyAllocator, MinifyError};
+///
+/// let mut alloc = MinifyAllocator::default();
+///
+/// assert_eq!(minify_bytes(b"{ a  b }", &mut alloc).unwrap(), "{a b}");
+/// assert_eq!(
+///     minify_bytes(b"{ a \xFF }", &mut alloc),
+///     Err(MinifyError::InvalidUtf8(4..5))
+/// );
+/// ```
+///
+/// # Errors
+///
+/// This function will return an error if the document is not valid UTF-8
+/// or the lexing process encounters an unexpected character.
+pub fn minify_bytes<T: AsRef<[u8]>>(
+    value: T,
+    alloc: &mut MinifyAllocator,
+) -> Result<String, MinifyError> {
     let value = value.as_ref();
-    let mut lexer = Token::lexer(value);
+
+    let value = std::str::from_utf8(value).map_err(|err| {
+        let start = err.valid_up_to();
+        let len = err.error_len().unwrap_or(value.len() - start);
+
+        MinifyError::InvalidUtf8(start..start + len)
+    })?;
+
+    minify(value, alloc)
+}
+
+/// Same as [`minify`], but reports every lexing error instead of stopping at the first one.
+///
+/// After an error, lexing is resumed right after the invalid token or, if an unknown token
+/// spans several lines (e.g. a string missing its closing quote), at the first line break inside it.
+///
+/// # Examples
+///
+/// ```
+/// use graphql_minify::{minify_all, MinifyAllocator, MinifyError};
+///
+/// let mut alloc = MinifyAllocator::default();
+/// let errors = minify_all("{ a % b ^ }", &mut alloc).unwrap_err();
+///
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[0], MinifyError::UnexpectedCharacter('%', 4..5));
+/// ```
+///
+/// # Errors
+///
+/// This function will return all errors encountered during the lexing process, if any.
+pub fn minify_all<T: AsRef<str>>(
+    value: T,
+    alloc: &mut MinifyAllocator,
+) -> Result<String, Vec<MinifyError>> {
+    minify_impl(
+        value.as_ref(),
+        alloc,
+        OnError::Collect,
+        &permissive_options(),
+    )
+    .map(|minified| minified.output)
+}
+
+/// options of [`minify`] and [`minify_all`], which accept syntax of all GraphQL proposals
+fn permissive_options() -> MinifyOptions {
+    MinifyOptions {
+        dialect: Dialect::EXPERIMENTAL,
+        ..MinifyOptions::default()
+    }
+}
+
+/// Same as [`minify_all`], but configurable with [`MinifyOptions`].
+///
+/// With [`MinifyOptions::recover`] set, invalid tokens are passed through verbatim,
+/// separated from the surrounding tokens, and the rest of the document is still minified.
+/// Such errors are returned in [`Minified::recovered`] instead of failing.
+///
+/// With [`Whitespace::Strict`], whitespace characters that GraphQL does not ignore are reported
+/// as unexpected characters instead of being stripped.
+///
+/// Syntax from GraphQL proposals is reported as [`MinifyError::UnsupportedSyntax`]
+/// unless enabled in [`MinifyOptions::dialect`]. With [`MinifyOptions::recover`] set, it is kept as is.
+///
+/// With [`MinifyOptions::strip_descriptions`] set, descriptions are removed, while string values are kept.
+/// Directives listed in [`MinifyOptions::remove_directives`] are removed with their arguments.
+///
+/// Transforms enabled in [`MinifyOptions::transform`] need the AST, so the document is parsed first.
+/// Documents that cannot be parsed fail with the syntax error, or are minified without the transforms
+/// when recovering.
+///
+/// # Examples
+///
+/// ```
+/// use graphql_minify::{minify_with_options, MinifyAllocator, MinifyOptions};
+///
+/// let mut alloc = MinifyAllocator::default();
+/// let options = MinifyOptions { recover: true, ..MinifyOptions::default() };
+/// let minified = minify_with_options("{ a  %  b }", &mut alloc, &options).unwrap();
+///
+/// assert_eq!(minified.output, "{a % b}");
+/// assert_eq!(minified.recovered.len(), 1);
+/// ```
+///
+/// # Errors
+///
+/// Unless recovering, this function will return all errors encountered during the lexing process, if any,
+/// or the syntax error if a transform is enabled.
+pub fn minify_with_options<T: AsRef<str>>(
+    value: T,
+    alloc: &mut MinifyAllocator,
+    options: &MinifyOptions,
+) -> Result<Minified, Vec<MinifyError>> {
+    let on_error = if options.recover {
+        OnError::Recover
+    } else {
+        OnError::Collect
+    };
+
+    if options.transform.is_enabled() {
+        let parse_options = ParseOptions {
+            whitespace: options.whitespace,
+            dialect: options.dialect,
+        };
+
+        match transform(value.as_ref(), &options.transform, &parse_options) {
+            Ok(transformed) => return minify_impl(&transformed, alloc, on_error, options),
+            Err(err) if !options.recover => return Err(vec![err]),
+            Err(_) => {}
+        }
+    }
+
+    minify_impl(value.as_ref(), alloc, on_error, options)
+}
+
+/// What to do when a lexing error occurs
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OnError {
+    /// stop at the first error
+    Stop,
+    /// continue lexing, collecting all errors
+    Collect,
+    /// pass the invalid token through verbatim and continue
+    Recover,
+}
+
+fn minify_impl(
+    value: &str,
+    alloc: &mut MinifyAllocator,
+    on_error: OnError,
+    options: &MinifyOptions,
+) -> Result<Minified, Vec<MinifyError>> {
+    let mut lexer = Token::lexer_with_extras(value, options.whitespace);
+    let mut dialect = DialectChecker::new(options.dialect);
+    let mut descriptions = options.strip_descriptions.then(DescriptionDetector::new);
+    let mut directives = DirectiveRemover::new(&options.remove_directives);
     let mut result = String::with_capacity(value.len());
     let mut last_token = None;
+    let mut errors = Vec::new();
+    // separator required after the token passed through verbatim
+    let mut separator = None;
 
     while let Some(token) = lexer.next() {
+        let mut block_string = None;
+        let token = match token {
+            Ok(Token::BlockStringDelimiter) => {
+                alloc.block_string.reset();
+                parse_block_string(&mut lexer, &alloc.block_string).map(|printed| {
+                    block_string = Some(printed);
+                    Token::BlockStringDelimiter
+                })
+            }
+            token => token,
+        };
         let token = match token {
             Ok(token) => token,
             Err(e) => {
-                return Err(match e {
-                    LexingError::UnknownToken => MinifyError::UnknownToken(lexer.span()),
-                    LexingError::UnterminatedString(span) => MinifyError::UnterminatedString(span),
-                });
+                let mut err = MinifyError::from_lexing_error(e, &lexer);
+
+                if on_error == OnError::Stop {
+                    errors.push(err);
+                    break;
+                }
+
+                if let MinifyError::UnknownToken(span) = &mut err
+                    && let Some(line_break) = resynchronize(&mut lexer)
+                {
+                    span.end = line_break;
+                }
+
+                let mut verbatim = err.span().clone();
+
+                // the rest of the document is the content of the unterminated block string
+                if let MinifyError::UnterminatedBlockString(_) = err {
+                    lexer.bump(lexer.remainder().len());
+                    verbatim.end = value.len();
+                }
+
+                if on_error == OnError::Recover {
+                    if let Some(separator) = separator.take() {
+                        result.push(separator);
+                    } else if !result.is_empty() {
+                        result.push(' ');
+                    }
+
+                    result.push_str(&value[verbatim.clone()]);
+                    last_token = None;
+                    // keep the line break, so that a token that was cut at it is not continued
+                    separator = Some(if value[verbatim.end..].starts_with(['\n', '\r']) {
+                        '\n'
+                    } else {
+                        ' '
+                    });
+                }
+
+                errors.push(err);
+                continue;
             }
         };
 
-        if needs_space(&token, last_token.as_ref()) {
-            result.push(' ');
+        // both see every token to keep track of the context
+        let is_description = descriptions
+            .as_mut()
+            .is_some_and(|descriptions| descriptions.is_description(&token));
+        let is_stripped = directives.is_removed(&token, lexer.slice()) || is_description;
+
+        if !is_stripped {
+            if let Some(separator) = separator.take() {
+                result.push(separator);
+            } else if needs_space(&token, last_token.as_ref()) {
+                result.push(' ');
+            }
+
+            match &block_string {
+                Some(block_string) => result.push_str(block_string.as_ref()),
+                None => result.push_str(lexer.slice()),
+            }
+            last_token = Some(token);
         }
 
-        match token {
-            Token::BlockStringDelimiter => {
-                result.push_str(parse_block_string(&mut lexer, &alloc.block_string).as_ref());
-                alloc.block_string.reset();
+        if let Some(err) = dialect.check(&token, lexer.slice(), lexer.span()) {
+            errors.push(err);
+
+            if on_error == OnError::Stop {
+                break;
             }
-            _ => result.push_str(lexer.slice()),
         }
-        last_token = Some(token);
     }
 
-    Ok(result)
+    alloc.block_string.reset();
+
+    if errors.is_empty() || on_error == OnError::Recover {
+        Ok(Minified {
+            output: result,
+            recovered: errors,
+        })
+    } else {
+        Err(errors)
+    }
+}
+
+/// moves the lexer that has just failed on an unknown token back to the first line break of the token,
+/// since tokens other than block strings cannot span several lines
+///
+/// Returns the offset of the line break, if the lexer was moved
+fn resynchronize(lexer: &mut Lexer<Token>) -> Option<usize> {
+    let line_break = lexer.span().start + lexer.slice().find(['\n', '\r'])?;
+
+    *lexer = Token::lexer_with_extras(lexer.source(), lexer.extras);
+    lexer.bump(line_break);
+
+    Some(line_break)
 }
 
 fn is_non_punctuator(token: &Token) -> bool {
@@ -132,7 +396,7 @@ fn needs_space_before_token(token: &Token) -> bool {
     matches!(token, Token::Identifier | Token::BlockStringDelimiter)
 }
 
-fn needs_space(cur_token: &Token, last_token: Option<&Token>) -> bool {
+pub(crate) fn needs_space(cur_token: &Token, last_token: Option<&Token>) -> bool {
     match last_token {
         Some(last) if is_non_punctuator(last) => is_non_punctuator(cur_token),
         Some(last) if needs_space_after_token(last) => needs_space_before_token(cur_token),
@@ -199,6 +463,163 @@ mod test {
         ));
     }
 
+    #[test]
+    fn collects_all_errors() {
+        let query = "{ a % b(arg: \"x\n\") ^ c(arg: \"y) d }";
+        let errors = super::minify_all(query, &mut crate::MinifyAllocator::default()).unwrap_err();
+
+        assert_eq!(
+            errors,
+            [
+                MinifyError::UnexpectedCharacter('%', 4..5),
+                MinifyError::UnterminatedString(13..17),
+                MinifyError::UnexpectedCharacter('^', 19..20),
+                MinifyError::UnknownToken(28..35),
+            ]
+        );
+    }
+
+    #[test]
+    fn minifies_spec_numbers() {
+        assert_eq!(
+            minify("{ a(x: 1e10, y: 1.5E+3, z: -0.0e-2) }").unwrap(),
+            "{a(x:1e10 y:1.5E+3 z:-0.0e-2)}"
+        );
+        assert_eq!(
+            minify("{ a(x: 123abc) }"),
+            Err(MinifyError::InvalidNumber(7..13))
+        );
+    }
+
+    #[test]
+    fn handles_unicode_whitespace() {
+        let query = "\u{feff}{ a\u{a0}b\u{2028}c d }";
+        let strict = crate::MinifyOptions {
+            whitespace: crate::Whitespace::Strict,
+            ..crate::MinifyOptions::default()
+        };
+
+        assert_eq!(minify(query).unwrap(), "{a b c d}");
+        assert_eq!(
+            super::minify_with_options(query, &mut crate::MinifyAllocator::default(), &strict)
+                .unwrap_err(),
+            [
+                MinifyError::UnexpectedCharacter('\u{a0}', 6..8),
+                MinifyError::UnexpectedCharacter('\u{2028}', 9..12),
+            ]
+        );
+        assert_eq!(
+            super::minify_with_options(
+                "\u{feff}{ a }",
+                &mut crate::MinifyAllocator::default(),
+                &strict
+            )
+            .unwrap()
+            .output,
+            "{a}"
+        );
+    }
+
+    #[test]
+    fn rejects_byte_order_mark_inside_document() {
+        let query = "{ a\u{feff}b }";
+        let strict = crate::MinifyOptions {
+            whitespace: crate::Whitespace::Strict,
+            ..crate::MinifyOptions::default()
+        };
+
+        assert_eq!(
+            minify(query),
+            Err(MinifyError::UnexpectedCharacter('\u{feff}', 3..6))
+        );
+        assert_eq!(
+            super::minify_with_options(query, &mut crate::MinifyAllocator::default(), &strict)
+                .unwrap_err(),
+            [MinifyError::UnexpectedCharacter('\u{feff}', 3..6)]
+        );
+    }
+
+    #[test]
+    fn rejects_extensions_not_in_dialect() {
+        let query = "fragment F($a: Int) on T { a? }";
+
+        assert_eq!(
+            super::minify_with_options(
+                query,
+                &mut crate::MinifyAllocator::default(),
+                &crate::MinifyOptions::default()
+            )
+            .unwrap_err(),
+            [
+                MinifyError::UnsupportedSyntax(crate::Extension::FragmentArguments, 10..11),
+                MinifyError::UnsupportedSyntax(
+                    crate::Extension::ClientControlledNullability,
+                    28..29
+                ),
+            ]
+        );
+        assert_eq!(minify(query).unwrap(), "fragment F($a:Int)on T{a?}");
+    }
+
+    #[test]
+    fn errs_on_unterminated_block_string() {
+        assert_eq!(
+            minify("\"\"\"abc"),
+            Err(MinifyError::UnterminatedBlockString(0..3))
+        );
+        assert_eq!(
+            minify("{ a(arg: \"\"\"b \\\"\"\" c) }"),
+            Err(MinifyError::UnterminatedBlockString(9..12))
+        );
+    }
+
+    #[test]
+    fn recovers_from_unterminated_block_string() {
+        let query = "{ a(arg: \"\"\"  b\n  c) }";
+        let options = crate::MinifyOptions {
+            recover: true,
+            ..crate::MinifyOptions::default()
+        };
+        let minified =
+            super::minify_with_options(query, &mut crate::MinifyAllocator::default(), &options)
+                .unwrap();
+
+        assert_eq!(minified.output, "{a(arg: \"\"\"  b\n  c) }");
+        assert_eq!(
+            minified.recovered,
+            [MinifyError::UnterminatedBlockString(9..12)]
+        );
+    }
+
+    #[test]
+    fn resumes_after_multiline_unknown_token() {
+        let query = "{ a(arg: \"x)\n % }";
+        let errors = super::minify_all(query, &mut crate::MinifyAllocator::default()).unwrap_err();
+
+        assert_eq!(
+            errors,
+            [
+                MinifyError::UnknownToken(9..12),
+                MinifyError::UnexpectedCharacter('%', 14..15),
+            ]
+        );
+    }
+
+    #[test]
+    fn recovers_from_errors() {
+        let query = "{ a  %  b(arg: 'x') c(arg: \"y)\n d }";
+        let options = crate::MinifyOptions {
+            recover: true,
+            ..crate::MinifyOptions::default()
+        };
+        let minified =
+            super::minify_with_options(query, &mut crate::MinifyAllocator::default(), &options)
+                .unwrap();
+
+        assert_eq!(minified.output, "{a % b(arg: ' x ' )c(arg: \"y)\nd}");
+        assert_eq!(minified.recovered.len(), 4);
+    }
+
     #[test]
     fn strips_non_parsable_document() {
         let query = r#"{ foo(arg: "str""#;
@@ -332,15 +753,131 @@ mod test {
         assert_eq!(minify(schema).unwrap(), expected);
     }
 
-    /// [`Token::String`]'s regex causes [stack overflow]
-    ///
-    /// release build optimizations help to avoid at least some of the stack overflows,
-    /// so this test is performed in release mode only
+    #[test]
+    fn strips_descriptions() {
+        let options = crate::MinifyOptions {
+            strip_descriptions: true,
+            ..crate::MinifyOptions::default()
+        };
+        let strip = |value: &str| {
+            super::minify_with_options(value, &mut crate::MinifyAllocator::default(), &options)
+                .unwrap()
+                .output
+        };
+
+        let schema = indoc! {r#"
+            """
+            Image
+            """
+            type Image @key(fields: "id") {
+              "URL" url(
+                "size in pixels" size: Int = 100
+                format: String = "png"
+              ): String @deprecated(reason: """Use `src`""")
+              tags(in: [String] = ["a" "b"]): [String]
+            }
+
+            "Format" enum Format { "Portable" PNG }
+            "Cached" directive @cached("seconds" ttl: Int) on FIELD
+        "#};
+
+        assert_eq!(
+            strip(schema),
+            r#"type Image@key(fields:"id"){url(size:Int=100 format:String="png"):String@deprecated(reason:"""Use `src`""")tags(in:[String]=["a" "b"]):[String]}enum Format{PNG}directive@cached(ttl:Int)on FIELD"#
+        );
+        assert_eq!(
+            strip(r#"query ($a: String = "a") { image(alt: "b") { url } }"#),
+            r#"query($a:String="a"){image(alt:"b"){url}}"#
+        );
+
+        let schema = include_str!("../test_data/valid/kitchen_sink_schema.graphql");
+        let stripped = strip(schema);
+        let arena = bumpalo::Bump::new();
+        let document = crate::parse(&stripped, &arena, &crate::ParseOptions::default()).unwrap();
+
+        assert!(
+            document
+                .definitions
+                .iter()
+                .all(|definition| match definition {
+                    crate::ast::Definition::Type(definition) => definition.description.is_none(),
+                    _ => true,
+                })
+        );
+    }
+
+    #[test]
+    fn removes_directives() {
+        let options = crate::MinifyOptions {
+            remove_directives: vec!["client".into(), "connection".into(), "key".into()],
+            ..crate::MinifyOptions::default()
+        };
+        let remove = |value: &str| {
+            super::minify_with_options(value, &mut crate::MinifyAllocator::default(), &options)
+                .unwrap()
+                .output
+        };
+
+        let query = indoc! {r#"
+            query Feed($first: Int) @client {
+              feed(first: $first) @connection(key: "feed", filter: ["type", "("]) {
+                id @include(if: true)
+                isLiked @client @export(as: """liked""")
+              }
+              clientOnly
+            }
+        "#};
+
+        assert_eq!(
+            remove(query),
+            r#"query Feed($first:Int){feed(first:$first){id@include(if:true)isLiked@export(as:"""liked""")}clientOnly}"#
+        );
+        assert_eq!(
+            remove(
+                r#"directive @key(fields: String) on OBJECT type Image @key(fields: "id") @shareable { id }"#
+            ),
+            "directive@key(fields:String)on OBJECT type Image@shareable{id}"
+        );
+        assert_eq!(
+            remove(r#"{ directive @client } "Key" directive @key on OBJECT"#),
+            r#"{directive}"Key" directive@key on OBJECT"#
+        );
+    }
+
+    #[test]
+    fn returns_directive_edits() {
+        let names = ["client".to_owned(), "connection".to_owned()];
+        let remove = |source: &str| {
+            super::apply_edits(source, &super::directive_edits(source, &names).unwrap())
+        };
+
+        assert_eq!(
+            remove(r#"{ feed @connection(key: "feed", filter: [KEY]) @client { id } }"#),
+            "{ feed  { id } }"
+        );
+        assert_eq!(
+            remove(r#"{ a @client(note: """ ) """) @include(if: true) b }"#),
+            "{ a  @include(if: true) b }"
+        );
+        assert_eq!(
+            remove("directive @client on FIELD"),
+            "directive @client on FIELD"
+        );
+        assert_eq!(
+            remove("{ directive @client(if: true) id }"),
+            "{ directive  id }"
+        );
+        assert!(matches!(
+            super::directive_edits(r#"{ a @client(note: "unterminated) }"#, &names),
+            Err(MinifyError::UnknownToken(_))
+        ));
+    }
+
+    /// [`Token::String`]'s regex used to cause [stack overflow], strings are scanned iteratively now
     ///
     /// [`Token::String`]: super::Token::String
     /// [stack overflow]: https://github.com/maciejhirsz/logos/issues/384
-    #[cfg_attr(not(debug_assertions), test)]
-    #[cfg_attr(debug_assertions, allow(dead_code))]
+    #[test]
     fn test_stack_overflow() {
         let schema = include_str!("../test_data/fuzz/stack_overflow");
         assert_eq!(
@@ -0,0 +1,330 @@
+//! Lint rules of executable documents that do not need a schema
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use logos::Span;
+
+use crate::ast::{Definition, Document, Name, OperationDefinition};
+use crate::codes::{self, ErrorCode};
+use crate::usages::Usages;
+
+/// Rule checked by the [`Linter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintRule {
+    /// operation without a name, including the query shorthand
+    AnonymousOperation,
+    /// operation with the name of an operation linted before
+    DuplicateOperationName,
+    /// variable that is defined by an operation but never used in it
+    UnusedVariable,
+    /// variable that is used in an operation but not defined by it
+    UndefinedVariable,
+    /// spread of a fragment that is not defined in the document
+    UnknownFragment,
+}
+
+impl LintRule {
+    pub const ALL: [LintRule; 5] = [
+        LintRule::AnonymousOperation,
+        LintRule::DuplicateOperationName,
+        LintRule::UnusedVariable,
+        LintRule::UndefinedVariable,
+        LintRule::UnknownFragment,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        self.error_code().message
+    }
+
+    /// returns the stable code of the rule, e.g. `GQLMIN016`
+    pub const fn code(self) -> &'static str {
+        self.error_code().code
+    }
+
+    /// returns the description of the rule from the [`codes`] catalogue
+    pub const fn error_code(self) -> &'static ErrorCode {
+        match self {
+            LintRule::AnonymousOperation => &codes::ANONYMOUS_OPERATION,
+            LintRule::DuplicateOperationName => &codes::DUPLICATE_OPERATION_NAME,
+            LintRule::UnusedVariable => &codes::UNUSED_VARIABLE,
+            LintRule::UndefinedVariable => &codes::UNDEFINED_VARIABLE,
+            LintRule::UnknownFragment => &codes::UNKNOWN_FRAGMENT,
+        }
+    }
+}
+
+/// Violation of a [`LintRule`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub rule: LintRule,
+    /// name of the operation, the variable or the fragment, empty for anonymous operations
+    pub name: String,
+    /// span of the name in the document, or of the operation keyword for anonymous operations
+    pub span: Span,
+}
+
+impl Lint {
+    /// returns the stable code of the violated rule
+    pub const fn code(&self) -> &'static str {
+        self.rule.code()
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = &self.name;
+
+        match self.rule {
+            LintRule::AnonymousOperation => f.write_str("operation has no name"),
+            LintRule::DuplicateOperationName => {
+                write!(f, "operation `{name}` is already defined")
+            }
+            LintRule::UnusedVariable => write!(f, "variable `${name}` is never used"),
+            LintRule::UndefinedVariable => write!(f, "variable `${name}` is not defined"),
+            LintRule::UnknownFragment => write!(f, "fragment `{name}` is not defined"),
+        }
+    }
+}
+
+/// Checks executable documents against enabled [`LintRule`]s
+///
+/// Names of operations are remembered between documents, so that duplicates are found
+/// across all documents of a module linted by the same linter.
+/// Variables used in spread fragments count as used by the operation.
+///
+/// # Examples
+///
+/// ```
+/// use graphql_minify::{Bump, Lint, LintRule, Linter, ParseOptions, parse};
+///
+/// let mut linter = Linter::new(LintRule::ALL);
+/// let arena = Bump::new();
+/// let document = parse("query ($id: ID) { image(id: $id, size: $size) { url } }", &arena, &ParseOptions::default()).unwrap();
+///
+/// assert_eq!(
+///     linter.lint(&document),
+///     [
+///         Lint { rule: LintRule::AnonymousOperation, name: String::new(), span: 0..5 },
+///         Lint { rule: LintRule::UndefinedVariable, name: "size".into(), span: 39..44 },
+///     ],
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub struct Linter {
+    rules: HashSet<LintRule>,
+    /// names of operations linted so far
+    operations: HashSet<String>,
+}
+
+impl Linter {
+    pub fn new(rules: impl IntoIterator<Item = LintRule>) -> Self {
+        Self {
+            rules: rules.into_iter().collect(),
+            operations: HashSet::new(),
+        }
+    }
+
+    /// Returns whether any rule is enabled.
+    pub fn is_enabled(&self) -> bool {
+        !self.rules.is_empty()
+    }
+
+    /// Lists violations of enabled rules in operations and fragments of the document.
+    ///
+    /// Type system definitions are skipped.
+    pub fn lint(&mut self, document: &Document) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        let fragments = document
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Fragment(fragment) => {
+                    Some((fragment.name.value, Usages::of_fragment(fragment)))
+                }
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+
+        // variables used in a fragment may be reported by every operation that spreads it
+        let mut undefined = HashSet::new();
+
+        for definition in &document.definitions {
+            let usages = match definition {
+                Definition::Operation(operation) => {
+                    let usages = Usages::of_operation(operation);
+                    self.operation(operation, &usages, &fragments, &mut undefined, &mut lints);
+                    usages
+                }
+                Definition::Fragment(fragment) => Usages::of_fragment(fragment),
+                Definition::Schema(_) | Definition::Type(_) | Definition::Directive(_) => {
+                    continue;
+                }
+            };
+
+            for spread in usages.spreads {
+                if !fragments.contains_key(spread.value) {
+                    self.push(&mut lints, LintRule::UnknownFragment, spread);
+                }
+            }
+        }
+
+        lints.sort_by_key(|lint| lint.span.start);
+        lints
+    }
+
+    fn operation<'d, 'a>(
+        &mut self,
+        operation: &OperationDefinition<'a>,
+        usages: &Usages<'d, 'a>,
+        fragments: &HashMap<&'a str, Usages<'d, 'a>>,
+        undefined: &mut HashSet<usize>,
+        lints: &mut Vec<Lint>,
+    ) {
+        match &operation.name {
+            Some(name) => {
+                if !self.operations.insert(name.value.to_owned()) {
+                    self.push(lints, LintRule::DuplicateOperationName, name);
+                }
+            }
+            None if self.rules.contains(&LintRule::AnonymousOperation) => lints.push(Lint {
+                rule: LintRule::AnonymousOperation,
+                name: String::new(),
+                span: operation.keyword_span(),
+            }),
+            None => {}
+        }
+
+        let (used, is_complete) = usages.transitive(fragments);
+        let defined = operation
+            .variable_definitions
+            .iter()
+            .map(|definition| definition.variable.value)
+            .collect::<HashSet<_>>();
+
+        for variable in used
+            .iter()
+            .filter(|variable| !defined.contains(variable.value))
+        {
+            if undefined.insert(variable.span.start) {
+                self.push(lints, LintRule::UndefinedVariable, variable);
+            }
+        }
+
+        // variables may be used in fragments that are not defined in the document
+        if is_complete {
+            for definition in &operation.variable_definitions {
+                let name = &definition.variable;
+
+                if !used.iter().any(|variable| variable.value == name.value) {
+                    self.push(lints, LintRule::UnusedVariable, name);
+                }
+            }
+        }
+    }
+
+    fn push(&self, lints: &mut Vec<Lint>, rule: LintRule, name: &Name) {
+        if self.rules.contains(&rule) {
+            lints.push(Lint {
+                rule,
+                name: name.value.to_owned(),
+                span: name.span.clone(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bumpalo::Bump;
+    use indoc::indoc;
+
+    use super::{Lint, LintRule, Linter};
+    use crate::{Dialect, ParseOptions, parse};
+
+    fn lint(linter: &mut Linter, source: &str) -> Vec<(LintRule, String)> {
+        let options = ParseOptions {
+            dialect: Dialect {
+                fragment_arguments: true,
+                ..Dialect::SPEC
+            },
+            ..ParseOptions::default()
+        };
+        let arena = Bump::new();
+
+        linter
+            .lint(&parse(source, &arena, &options).unwrap())
+            .into_iter()
+            .map(|Lint { rule, name, .. }| (rule, name))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_valid_documents() {
+        let source = indoc! {r"
+            query Images($ids: [ID!], $size: Int, $skip: Boolean!) @live(if: $skip) {
+              images(filter: { ids: $ids }) { ...img }
+            }
+
+            fragment img on Image { url(size: $size) ...thumb(width: 10) }
+            fragment thumb($width: Int) on Image { thumb(width: $width) }
+        "};
+
+        assert_eq!(lint(&mut Linter::new(LintRule::ALL), source), []);
+    }
+
+    #[test]
+    fn reports_operation_names() {
+        let mut linter = Linter::new(LintRule::ALL);
+
+        assert_eq!(
+            lint(&mut linter, "{ a } mutation { b } query A { c }"),
+            [
+                (LintRule::AnonymousOperation, String::new()),
+                (LintRule::AnonymousOperation, String::new()),
+            ]
+        );
+        assert_eq!(
+            lint(&mut linter, "query A { c } query B { d } query B { e }"),
+            [
+                (LintRule::DuplicateOperationName, "A".into()),
+                (LintRule::DuplicateOperationName, "B".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_variables() {
+        let source = indoc! {r"
+            query A($a: ID, $b: ID) { f(a: $a, c: $c) { ...F } }
+            query B($d: ID) { f(d: $d) { ...F } }
+            query C($e: ID) { ...Unknown }
+
+            fragment F on T { g(x: [{ y: $x }]) }
+        "};
+
+        assert_eq!(
+            lint(&mut Linter::new(LintRule::ALL), source),
+            [
+                (LintRule::UnusedVariable, "b".into()),
+                (LintRule::UndefinedVariable, "c".into()),
+                (LintRule::UnknownFragment, "Unknown".into()),
+                (LintRule::UndefinedVariable, "x".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_enabled_rules_only() {
+        let mut linter = Linter::new([LintRule::UnknownFragment]);
+
+        assert_eq!(
+            lint(
+                &mut linter,
+                "query ($a: ID) { ...F } query ($a: ID) { b(c: $c) }"
+            ),
+            [(LintRule::UnknownFragment, "F".into())]
+        );
+        assert!(!Linter::default().is_enabled());
+    }
+}
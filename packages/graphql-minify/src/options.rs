@@ -0,0 +1,40 @@
+use crate::{Dialect, MinifyError, TransformOptions};
+
+/// Options of [`minify_with_options`](crate::minify_with_options)
+#[derive(Debug, Default, Clone)]
+pub struct MinifyOptions {
+    /// pass invalid tokens through verbatim instead of failing, minifying the rest of the document
+    pub recover: bool,
+    /// which whitespace characters are ignored between tokens
+    pub whitespace: Whitespace,
+    /// experimental syntax accepted in addition to the spec
+    pub dialect: Dialect,
+    /// remove descriptions of definitions, fields, arguments and enum values, e.g. from schemas shipped to clients
+    pub strip_descriptions: bool,
+    /// names of directives without `@` to remove with their arguments, e.g. client-only directives
+    pub remove_directives: Vec<String>,
+    /// transforms applied to the parsed document before minification
+    pub transform: TransformOptions,
+}
+
+/// Handling of whitespace characters that are not GraphQL ignored tokens
+///
+/// GraphQL ignores only tabs, spaces, line terminators and commas, as well as the byte order mark
+/// at the start of the document, which is skipped in both modes and reported anywhere else
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Whitespace {
+    /// other Unicode whitespace characters (e.g. NBSP or U+2028) are ignored as well
+    #[default]
+    Lenient,
+    /// other Unicode whitespace characters are reported as unexpected characters
+    Strict,
+}
+
+/// Result of [`minify_with_options`](crate::minify_with_options)
+#[derive(Debug)]
+pub struct Minified {
+    /// minified document
+    pub output: String,
+    /// errors whose tokens were passed through verbatim, empty unless [`MinifyOptions::recover`] is set
+    pub recovered: Vec<MinifyError>,
+}
@@ -0,0 +1,1240 @@
+use bumpalo::Bump;
+use bumpalo::collections::Vec;
+use logos::{Lexer, Logos, Span};
+
+use crate::ast::{
+    Argument, Definition, Directive, DirectiveDefinition, Document, EnumValueDefinition, Field,
+    FieldDefinition, FragmentDefinition, FragmentSpread, InlineFragment, InputValueDefinition,
+    Name, Nullability, ObjectField, OperationDefinition, OperationType, RootOperationType,
+    SchemaDefinition, Selection, SelectionSet, StringValue, Type, TypeDefinition, TypeKind, Value,
+    VariableDefinition,
+};
+use crate::lexer::{Token, skip_block_string};
+use crate::{Dialect, Extension, MinifyError, Whitespace};
+
+/// Options of [`parse`] and [`parse_selections`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// which whitespace characters are ignored between tokens
+    pub whitespace: Whitespace,
+    /// experimental syntax accepted in addition to the spec
+    pub dialect: Dialect,
+}
+
+/// Parses a GraphQL document into an AST allocated in `arena`.
+///
+/// Both executable and type system definitions are accepted, the document is not validated
+/// against any schema.
+///
+/// # Examples
+///
+/// ```
+/// use graphql_minify::ast::{Definition, Selection};
+/// use graphql_minify::{Bump, ParseOptions, parse};
+///
+/// let arena = Bump::new();
+/// let document = parse("query Q { image { id } }", &arena, &ParseOptions::default()).unwrap();
+///
+/// let Definition::Operation(operation) = &document.definitions[0] else { unreachable!() };
+/// assert_eq!(operation.name.as_ref().unwrap().value, "Q");
+/// assert!(matches!(operation.selection_set.selections[0], Selection::Field(_)));
+/// ```
+///
+/// # Errors
+///
+/// This function will return the first lexing or syntax error of the document.
+pub fn parse<'a>(
+    source: &'a str,
+    arena: &'a Bump,
+    options: &ParseOptions,
+) -> Result<Document<'a>, MinifyError> {
+    let mut parser = Parser::new(source, arena, options)?;
+    let mut definitions = Vec::new_in(arena);
+
+    loop {
+        definitions.push(parser.definition()?);
+
+        if parser.peeked.is_none() {
+            break;
+        }
+    }
+
+    Ok(Document {
+        definitions,
+        span: 0..source.len(),
+    })
+}
+
+/// Parses the contents of a selection set without the enclosing braces, e.g. `id image { url }`,
+/// into an AST allocated in `arena`.
+///
+/// # Examples
+///
+/// ```
+/// use graphql_minify::{Bump, ParseOptions, parse_selections};
+///
+/// let arena = Bump::new();
+/// let selections = parse_selections("id ...img", &arena, &ParseOptions::default()).unwrap();
+///
+/// assert_eq!(selections.len(), 2);
+/// ```
+///
+/// # Errors
+///
+/// This function will return the first lexing or syntax error of the document.
+pub fn parse_selections<'a>(
+    source: &'a str,
+    arena: &'a Bump,
+    options: &ParseOptions,
+) -> Result<Vec<'a, Selection<'a>>, MinifyError> {
+    let mut parser = Parser::new(source, arena, options)?;
+    let mut selections = Vec::new_in(arena);
+
+    loop {
+        selections.push(parser.selection()?);
+
+        if parser.peeked.is_none() {
+            break;
+        }
+    }
+
+    Ok(selections)
+}
+
+/// Maximum nesting depth of lists, objects, selection sets, types and other delimited items
+///
+/// The parser is recursive, so deeper documents are rejected instead of overflowing the stack
+const MAX_DEPTH: usize = 128;
+/// expectation of the syntax error past [`MAX_DEPTH`]
+const TOO_DEEP: &str = "at most 128 levels of nesting";
+
+/// Recursive descent parser with a single token of lookahead
+struct Parser<'a> {
+    source: &'a str,
+    arena: &'a Bump,
+    dialect: Dialect,
+    lexer: Lexer<'a, Token>,
+    /// next token, `None` at the end of the document
+    peeked: Option<(Token, Span)>,
+    /// end of the last consumed token
+    end: usize,
+    /// current nesting depth, at most [`MAX_DEPTH`]
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str, arena: &'a Bump, options: &ParseOptions) -> Result<Self, MinifyError> {
+        let mut parser = Self {
+            source,
+            arena,
+            dialect: options.dialect,
+            lexer: Token::lexer_with_extras(source, options.whitespace),
+            peeked: None,
+            end: 0,
+            depth: 0,
+        };
+        parser.peeked = parser.lex()?;
+
+        Ok(parser)
+    }
+
+    fn lex(&mut self) -> Result<Option<(Token, Span)>, MinifyError> {
+        match self.lexer.next() {
+            Some(Ok(token)) => {
+                if token == Token::BlockStringDelimiter {
+                    skip_block_string(&mut self.lexer)
+                        .map_err(|e| MinifyError::from_lexing_error(e, &self.lexer))?;
+                }
+
+                Ok(Some((token, self.lexer.span())))
+            }
+            Some(Err(e)) => Err(MinifyError::from_lexing_error(e, &self.lexer)),
+            None => Ok(None),
+        }
+    }
+
+    fn peek(&self) -> Option<Token> {
+        self.peeked.as_ref().map(|(token, _)| *token)
+    }
+
+    fn peek_start(&self) -> usize {
+        self.peeked
+            .as_ref()
+            .map_or(self.source.len(), |(_, span)| span.start)
+    }
+
+    /// returns whether the next token is a name equal to `keyword`
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.peeked, Some((Token::Identifier, span)) if &self.source[span.clone()] == keyword)
+    }
+
+    fn peek_name(&self) -> bool {
+        matches!(self.peek(), Some(Token::Identifier | Token::Bool))
+    }
+
+    /// consumes the next token, which must exist
+    fn bump(&mut self) -> Result<(Token, Span), MinifyError> {
+        let next = self.lex()?;
+        let (token, span) = std::mem::replace(&mut self.peeked, next)
+            .expect("bump is called only when there is the next token");
+        self.end = span.end;
+
+        Ok((token, span))
+    }
+
+    /// consumes the next token if it is `token`
+    fn eat(&mut self, token: Token) -> Result<bool, MinifyError> {
+        if self.peek() == Some(token) {
+            self.bump()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn expect(&mut self, token: Token, expected: &'static str) -> Result<Span, MinifyError> {
+        if self.peek() == Some(token) {
+            Ok(self.bump()?.1)
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &'static str) -> Result<Span, MinifyError> {
+        if self.peek_keyword(keyword) {
+            Ok(self.bump()?.1)
+        } else {
+            Err(self.unexpected(keyword))
+        }
+    }
+
+    /// returns the error for the next token, which is not the `expected` one
+    fn unexpected(&self, expected: &'static str) -> MinifyError {
+        let span = self
+            .peeked
+            .as_ref()
+            .map_or(self.source.len()..self.source.len(), |(_, span)| {
+                span.clone()
+            });
+
+        MinifyError::SyntaxError(expected, span)
+    }
+
+    /// returns an error if the extension used at `span` is not enabled
+    fn allow(&self, extension: Extension, span: &Span) -> Result<(), MinifyError> {
+        if self.dialect.allows(extension) {
+            Ok(())
+        } else {
+            Err(MinifyError::UnsupportedSyntax(extension, span.clone()))
+        }
+    }
+
+    fn span_from(&self, start: usize) -> Span {
+        start..self.end
+    }
+
+    /// parses with `parse` one level deeper, failing at the next token past [`MAX_DEPTH`]
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, MinifyError>,
+    ) -> Result<T, MinifyError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.unexpected(TOO_DEEP));
+        }
+
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+
+        result
+    }
+
+    /// parses items until `close`, which must follow at least one item if `non_empty`
+    fn delimited<T>(
+        &mut self,
+        open: Token,
+        close: Token,
+        expected: &'static str,
+        non_empty: bool,
+        mut item: impl FnMut(&mut Self) -> Result<T, MinifyError>,
+    ) -> Result<Vec<'a, T>, MinifyError> {
+        self.nested(|parser| {
+            let mut items = Vec::new_in(parser.arena);
+
+            parser.expect(open, expected)?;
+
+            while !parser.eat(close)? {
+                if parser.peeked.is_none() {
+                    return Err(parser.unexpected(expected));
+                }
+
+                items.push(item(parser)?);
+            }
+
+            if non_empty && items.is_empty() {
+                return Err(MinifyError::SyntaxError(
+                    expected,
+                    parser.end - 1..parser.end,
+                ));
+            }
+
+            Ok(items)
+        })
+    }
+
+    fn name(&mut self, expected: &'static str) -> Result<Name<'a>, MinifyError> {
+        if !self.peek_name() {
+            return Err(self.unexpected(expected));
+        }
+
+        let (_, span) = self.bump()?;
+
+        Ok(Name {
+            value: &self.source[span.clone()],
+            span,
+        })
+    }
+
+    /// parses `$name` or `@name` as a name without the sigil
+    fn sigil_name(
+        &mut self,
+        token: Token,
+        expected: &'static str,
+    ) -> Result<Name<'a>, MinifyError> {
+        let span = self.expect(token, expected)?;
+
+        Ok(Name {
+            value: &self.source[span.start + 1..span.end],
+            span,
+        })
+    }
+
+    fn description(&mut self) -> Result<Option<StringValue<'a>>, MinifyError> {
+        match self.peek() {
+            Some(Token::String | Token::BlockStringDelimiter) => self.string().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn string(&mut self) -> Result<StringValue<'a>, MinifyError> {
+        let (token, span) = self.bump()?;
+
+        Ok(StringValue {
+            raw: &self.source[span.clone()],
+            block: token == Token::BlockStringDelimiter,
+            span,
+        })
+    }
+
+    fn definition(&mut self) -> Result<Definition<'a>, MinifyError> {
+        const EXPECTED: &str = "a definition";
+
+        let start = self.peek_start();
+        let description = self.description()?;
+
+        if let Some(description) = &description
+            && (self.peek() == Some(Token::BraceOpen)
+                || self.peek_keyword("fragment")
+                || self.peek_operation_type())
+        {
+            self.allow(Extension::OperationDescriptions, &description.span)?;
+        }
+
+        if self.peek() == Some(Token::BraceOpen) {
+            let selection_set = self.selection_set()?;
+
+            return Ok(Definition::Operation(OperationDefinition {
+                description,
+                operation: OperationType::Query,
+                name: None,
+                variable_definitions: Vec::new_in(self.arena),
+                directives: Vec::new_in(self.arena),
+                selection_set,
+                span: self.span_from(start),
+            }));
+        }
+
+        if !self.peek_name() {
+            return Err(self.unexpected(EXPECTED));
+        }
+
+        let keyword = &self.source[self.peeked.as_ref().map_or(0..0, |(_, span)| span.clone())];
+
+        match keyword {
+            "query" | "mutation" | "subscription" => self.operation(description, start),
+            "fragment" => self.fragment(description, start),
+            "extend" if description.is_none() => {
+                self.bump()?;
+                self.type_system_definition(None, true, start)
+            }
+            "schema" | "scalar" | "type" | "interface" | "union" | "enum" | "input"
+            | "directive" => self.type_system_definition(description, false, start),
+            _ => Err(self.unexpected(EXPECTED)),
+        }
+    }
+
+    fn peek_operation_type(&self) -> bool {
+        ["query", "mutation", "subscription"]
+            .iter()
+            .any(|keyword| self.peek_keyword(keyword))
+    }
+
+    fn operation_type(&mut self) -> Result<OperationType, MinifyError> {
+        let name = self.name("an operation type")?;
+
+        OperationType::from_keyword(name.value)
+            .ok_or(MinifyError::SyntaxError("an operation type", name.span))
+    }
+
+    fn operation(
+        &mut self,
+        description: Option<StringValue<'a>>,
+        start: usize,
+    ) -> Result<Definition<'a>, MinifyError> {
+        let operation = self.operation_type()?;
+        let name = if self.peek_name() {
+            Some(self.name("a name")?)
+        } else {
+            None
+        };
+        let variable_definitions = self.variable_definitions()?;
+        let directives = self.directives(false)?;
+        let selection_set = self.selection_set()?;
+
+        Ok(Definition::Operation(OperationDefinition {
+            description,
+            operation,
+            name,
+            variable_definitions,
+            directives,
+            selection_set,
+            span: self.span_from(start),
+        }))
+    }
+
+    fn fragment(
+        &mut self,
+        description: Option<StringValue<'a>>,
+        start: usize,
+    ) -> Result<Definition<'a>, MinifyError> {
+        self.expect_keyword("fragment")?;
+
+        if self.peek_keyword("on") {
+            return Err(self.unexpected("a fragment name"));
+        }
+
+        let name = self.name("a fragment name")?;
+
+        if let Some((Token::ParenOpen, span)) = &self.peeked {
+            self.allow(Extension::FragmentArguments, span)?;
+        }
+
+        let variable_definitions = self.variable_definitions()?;
+        let type_condition = self.type_condition()?;
+        let directives = self.directives(false)?;
+        let selection_set = self.selection_set()?;
+
+        Ok(Definition::Fragment(FragmentDefinition {
+            description,
+            name,
+            variable_definitions,
+            type_condition,
+            directives,
+            selection_set,
+            span: self.span_from(start),
+        }))
+    }
+
+    fn type_condition(&mut self) -> Result<Name<'a>, MinifyError> {
+        self.expect_keyword("on")?;
+        self.name("a type name")
+    }
+
+    fn variable_definitions(&mut self) -> Result<Vec<'a, VariableDefinition<'a>>, MinifyError> {
+        if self.peek() != Some(Token::ParenOpen) {
+            return Ok(Vec::new_in(self.arena));
+        }
+
+        self.delimited(
+            Token::ParenOpen,
+            Token::ParenClose,
+            "a variable definition",
+            true,
+            |parser| {
+                let start = parser.peek_start();
+                let variable = parser.sigil_name(Token::Variable, "a variable")?;
+                parser.expect(Token::Colon, "`:`")?;
+                let ty = parser.ty()?;
+                let default_value = parser.default_value()?;
+                let directives = parser.directives(true)?;
+
+                Ok(VariableDefinition {
+                    variable,
+                    ty,
+                    default_value,
+                    directives,
+                    span: parser.span_from(start),
+                })
+            },
+        )
+    }
+
+    fn default_value(&mut self) -> Result<Option<Value<'a>>, MinifyError> {
+        if self.eat(Token::Equals)? {
+            self.value(true).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn ty(&mut self) -> Result<Type<'a>, MinifyError> {
+        let start = self.peek_start();
+
+        let ty = if self.peek() == Some(Token::BracketOpen) {
+            let element = self.nested(|parser| {
+                parser.bump()?;
+                parser.ty()
+            })?;
+            self.expect(Token::BracketClose, "`]`")?;
+            Type::List(self.arena.alloc(element), self.span_from(start))
+        } else {
+            Type::Named(self.name("a type")?)
+        };
+
+        if self.eat(Token::Exclamation)? {
+            Ok(Type::NonNull(self.arena.alloc(ty), self.span_from(start)))
+        } else {
+            Ok(ty)
+        }
+    }
+
+    fn selection_set(&mut self) -> Result<SelectionSet<'a>, MinifyError> {
+        let start = self.peek_start();
+        let selections = self.delimited(
+            Token::BraceOpen,
+            Token::BraceClose,
+            "a selection set",
+            true,
+            Self::selection,
+        )?;
+
+        Ok(SelectionSet {
+            selections,
+            span: self.span_from(start),
+        })
+    }
+
+    fn selection(&mut self) -> Result<Selection<'a>, MinifyError> {
+        let start = self.peek_start();
+
+        if !self.eat(Token::Ellipsis)? {
+            return self.field(start).map(Selection::Field);
+        }
+
+        if self.peek_name() && !self.peek_keyword("on") {
+            let name = self.name("a fragment name")?;
+
+            if let Some((Token::ParenOpen, span)) = &self.peeked {
+                self.allow(Extension::FragmentArguments, span)?;
+            }
+
+            let arguments = self.arguments(false)?;
+            let directives = self.directives(false)?;
+
+            return Ok(Selection::FragmentSpread(FragmentSpread {
+                name,
+                arguments,
+                directives,
+                span: self.span_from(start),
+            }));
+        }
+
+        let type_condition = if self.peek_keyword("on") {
+            Some(self.type_condition()?)
+        } else {
+            None
+        };
+        let directives = self.directives(false)?;
+        let selection_set = self.selection_set()?;
+
+        Ok(Selection::InlineFragment(InlineFragment {
+            type_condition,
+            directives,
+            selection_set,
+            span: self.span_from(start),
+        }))
+    }
+
+    fn field(&mut self, start: usize) -> Result<Field<'a>, MinifyError> {
+        let mut name = self.name("a selection")?;
+        let mut alias = None;
+
+        if self.eat(Token::Colon)? {
+            alias = Some(name);
+            name = self.name("a field name")?;
+        }
+
+        let arguments = self.arguments(false)?;
+        let nullability = self.nullability()?;
+        let directives = self.directives(false)?;
+        let selection_set = if self.peek() == Some(Token::BraceOpen) {
+            Some(self.selection_set()?)
+        } else {
+            None
+        };
+
+        Ok(Field {
+            alias,
+            name,
+            arguments,
+            nullability,
+            directives,
+            selection_set,
+            span: self.span_from(start),
+        })
+    }
+
+    fn nullability(&mut self) -> Result<Option<Nullability<'a>>, MinifyError> {
+        let Some((token, span)) = self.peeked.clone() else {
+            return Ok(None);
+        };
+
+        match token {
+            Token::Exclamation | Token::Question | Token::BracketOpen => {
+                self.allow(Extension::ClientControlledNullability, &span)?;
+            }
+            _ => return Ok(None),
+        }
+
+        self.bump()?;
+
+        Ok(Some(match token {
+            Token::Exclamation => Nullability::Required(span),
+            Token::Question => Nullability::Optional(span),
+            _ => {
+                let element = self.nested(Self::nullability)?;
+                self.expect(Token::BracketClose, "`]`")?;
+                let designator = match self.peek() {
+                    Some(Token::Exclamation | Token::Question) => self.nullability()?,
+                    _ => None,
+                };
+
+                Nullability::List {
+                    element: element.map(|element| &*self.arena.alloc(element)),
+                    designator: designator.map(|designator| &*self.arena.alloc(designator)),
+                    span: self.span_from(span.start),
+                }
+            }
+        }))
+    }
+
+    fn arguments(&mut self, is_const: bool) -> Result<Vec<'a, Argument<'a>>, MinifyError> {
+        if self.peek() != Some(Token::ParenOpen) {
+            return Ok(Vec::new_in(self.arena));
+        }
+
+        self.delimited(
+            Token::ParenOpen,
+            Token::ParenClose,
+            "an argument",
+            true,
+            |parser| {
+                let start = parser.peek_start();
+                let name = parser.name("an argument")?;
+                parser.expect(Token::Colon, "`:`")?;
+                let value = parser.value(is_const)?;
+
+                Ok(Argument {
+                    name,
+                    value,
+                    span: parser.span_from(start),
+                })
+            },
+        )
+    }
+
+    fn directives(&mut self, is_const: bool) -> Result<Vec<'a, Directive<'a>>, MinifyError> {
+        let mut directives = Vec::new_in(self.arena);
+
+        while self.peek() == Some(Token::Directive) {
+            let start = self.peek_start();
+            let name = self.sigil_name(Token::Directive, "a directive")?;
+            let arguments = self.arguments(is_const)?;
+
+            directives.push(Directive {
+                name,
+                arguments,
+                span: self.span_from(start),
+            });
+        }
+
+        Ok(directives)
+    }
+
+    fn value(&mut self, is_const: bool) -> Result<Value<'a>, MinifyError> {
+        const EXPECTED: &str = "a value";
+
+        let start = self.peek_start();
+
+        match self.peek() {
+            Some(Token::Variable) if is_const => Err(self.unexpected("a constant value")),
+            Some(Token::Variable) => self
+                .sigil_name(Token::Variable, EXPECTED)
+                .map(Value::Variable),
+            Some(Token::Int) => {
+                let (_, span) = self.bump()?;
+                Ok(Value::Int(&self.source[span.clone()], span))
+            }
+            Some(Token::Float) => {
+                let (_, span) = self.bump()?;
+                Ok(Value::Float(&self.source[span.clone()], span))
+            }
+            Some(Token::String | Token::BlockStringDelimiter) => self.string().map(Value::String),
+            Some(Token::Bool) => {
+                let (_, span) = self.bump()?;
+                Ok(Value::Boolean(&self.source[span.clone()] == "true", span))
+            }
+            Some(Token::Identifier) => {
+                let name = self.name(EXPECTED)?;
+
+                Ok(if name.value == "null" {
+                    Value::Null(name.span)
+                } else {
+                    Value::Enum(name)
+                })
+            }
+            Some(Token::BracketOpen) => {
+                let values = self.delimited(
+                    Token::BracketOpen,
+                    Token::BracketClose,
+                    EXPECTED,
+                    false,
+                    |parser| parser.value(is_const),
+                )?;
+
+                Ok(Value::List(values, self.span_from(start)))
+            }
+            Some(Token::BraceOpen) => {
+                let fields = self.delimited(
+                    Token::BraceOpen,
+                    Token::BraceClose,
+                    "an object field",
+                    false,
+                    |parser| {
+                        let start = parser.peek_start();
+                        let name = parser.name("an object field")?;
+                        parser.expect(Token::Colon, "`:`")?;
+                        let value = parser.value(is_const)?;
+
+                        Ok(ObjectField {
+                            name,
+                            value,
+                            span: parser.span_from(start),
+                        })
+                    },
+                )?;
+
+                Ok(Value::Object(fields, self.span_from(start)))
+            }
+            _ => Err(self.unexpected(EXPECTED)),
+        }
+    }
+
+    fn type_system_definition(
+        &mut self,
+        description: Option<StringValue<'a>>,
+        extension: bool,
+        start: usize,
+    ) -> Result<Definition<'a>, MinifyError> {
+        const EXPECTED: &str = "a type system definition";
+
+        let keyword = self.name(EXPECTED)?;
+
+        match keyword.value {
+            "schema" => self.schema(description, extension, start),
+            "directive" if !extension => self.directive_definition(description, start),
+            "scalar" | "type" | "interface" | "union" | "enum" | "input" => {
+                self.type_definition(keyword.value, description, extension, start)
+            }
+            _ => Err(MinifyError::SyntaxError(EXPECTED, keyword.span)),
+        }
+    }
+
+    fn schema(
+        &mut self,
+        description: Option<StringValue<'a>>,
+        extension: bool,
+        start: usize,
+    ) -> Result<Definition<'a>, MinifyError> {
+        let directives = self.directives(true)?;
+        let operation_types = if !extension || self.peek() == Some(Token::BraceOpen) {
+            self.delimited(
+                Token::BraceOpen,
+                Token::BraceClose,
+                "a root operation type",
+                true,
+                |parser| {
+                    let start = parser.peek_start();
+                    let operation = parser.operation_type()?;
+                    parser.expect(Token::Colon, "`:`")?;
+                    let ty = parser.name("a type name")?;
+
+                    Ok(RootOperationType {
+                        operation,
+                        ty,
+                        span: parser.span_from(start),
+                    })
+                },
+            )?
+        } else {
+            Vec::new_in(self.arena)
+        };
+
+        Ok(Definition::Schema(SchemaDefinition {
+            description,
+            extension,
+            directives,
+            operation_types,
+            span: self.span_from(start),
+        }))
+    }
+
+    fn type_definition(
+        &mut self,
+        keyword: &str,
+        description: Option<StringValue<'a>>,
+        extension: bool,
+        start: usize,
+    ) -> Result<Definition<'a>, MinifyError> {
+        let name = self.name("a type name")?;
+
+        let interfaces = match keyword {
+            "type" | "interface" => self.implements_interfaces()?,
+            _ => Vec::new_in(self.arena),
+        };
+        let directives = self.directives(true)?;
+
+        let kind = match keyword {
+            "scalar" => TypeKind::Scalar,
+            "type" => TypeKind::Object {
+                interfaces,
+                fields: self.fields_definition()?,
+            },
+            "interface" => TypeKind::Interface {
+                interfaces,
+                fields: self.fields_definition()?,
+            },
+            "union" => TypeKind::Union {
+                members: self.union_members()?,
+            },
+            "enum" => TypeKind::Enum {
+                values: self.optional_body("an enum value", Self::enum_value_definition)?,
+            },
+            _ => TypeKind::InputObject {
+                fields: self.optional_body("an input field", Self::input_value_definition)?,
+            },
+        };
+
+        Ok(Definition::Type(TypeDefinition {
+            description,
+            extension,
+            name,
+            directives,
+            kind,
+            span: self.span_from(start),
+        }))
+    }
+
+    fn implements_interfaces(&mut self) -> Result<Vec<'a, Name<'a>>, MinifyError> {
+        let mut interfaces = Vec::new_in(self.arena);
+
+        if self.peek_keyword("implements") {
+            self.bump()?;
+            self.eat(Token::Ampersand)?;
+
+            loop {
+                interfaces.push(self.name("an interface name")?);
+
+                if !self.eat(Token::Ampersand)? {
+                    break;
+                }
+            }
+        }
+
+        Ok(interfaces)
+    }
+
+    fn union_members(&mut self) -> Result<Vec<'a, Name<'a>>, MinifyError> {
+        let mut members = Vec::new_in(self.arena);
+
+        if self.eat(Token::Equals)? {
+            self.eat(Token::Pipe)?;
+
+            loop {
+                members.push(self.name("a union member")?);
+
+                if !self.eat(Token::Pipe)? {
+                    break;
+                }
+            }
+        }
+
+        Ok(members)
+    }
+
+    /// parses the braced body of a type definition, if any
+    fn optional_body<T>(
+        &mut self,
+        expected: &'static str,
+        item: impl FnMut(&mut Self) -> Result<T, MinifyError>,
+    ) -> Result<Vec<'a, T>, MinifyError> {
+        if self.peek() == Some(Token::BraceOpen) {
+            self.delimited(Token::BraceOpen, Token::BraceClose, expected, true, item)
+        } else {
+            Ok(Vec::new_in(self.arena))
+        }
+    }
+
+    fn fields_definition(&mut self) -> Result<Vec<'a, FieldDefinition<'a>>, MinifyError> {
+        self.optional_body("a field definition", |parser| {
+            let start = parser.peek_start();
+            let description = parser.description()?;
+            let name = parser.name("a field definition")?;
+            let arguments = parser.arguments_definition()?;
+            parser.expect(Token::Colon, "`:`")?;
+            let ty = parser.ty()?;
+            let directives = parser.directives(true)?;
+
+            Ok(FieldDefinition {
+                description,
+                name,
+                arguments,
+                ty,
+                directives,
+                span: parser.span_from(start),
+            })
+        })
+    }
+
+    fn arguments_definition(&mut self) -> Result<Vec<'a, InputValueDefinition<'a>>, MinifyError> {
+        if self.peek() != Some(Token::ParenOpen) {
+            return Ok(Vec::new_in(self.arena));
+        }
+
+        self.delimited(
+            Token::ParenOpen,
+            Token::ParenClose,
+            "an argument definition",
+            true,
+            Self::input_value_definition,
+        )
+    }
+
+    fn input_value_definition(&mut self) -> Result<InputValueDefinition<'a>, MinifyError> {
+        let start = self.peek_start();
+        let description = self.description()?;
+        let name = self.name("an input value definition")?;
+        self.expect(Token::Colon, "`:`")?;
+        let ty = self.ty()?;
+        let default_value = self.default_value()?;
+        let directives = self.directives(true)?;
+
+        Ok(InputValueDefinition {
+            description,
+            name,
+            ty,
+            default_value,
+            directives,
+            span: self.span_from(start),
+        })
+    }
+
+    fn enum_value_definition(&mut self) -> Result<EnumValueDefinition<'a>, MinifyError> {
+        const EXPECTED: &str = "an enum value";
+
+        let start = self.peek_start();
+        let description = self.description()?;
+
+        if ["true", "false", "null"]
+            .iter()
+            .any(|keyword| self.peek_keyword(keyword))
+            || self.peek() == Some(Token::Bool)
+        {
+            return Err(self.unexpected(EXPECTED));
+        }
+
+        let name = self.name(EXPECTED)?;
+        let directives = self.directives(true)?;
+
+        Ok(EnumValueDefinition {
+            description,
+            name,
+            directives,
+            span: self.span_from(start),
+        })
+    }
+
+    fn directive_definition(
+        &mut self,
+        description: Option<StringValue<'a>>,
+        start: usize,
+    ) -> Result<Definition<'a>, MinifyError> {
+        let name = self.sigil_name(Token::Directive, "a directive name")?;
+        let arguments = self.arguments_definition()?;
+        let repeatable = self.peek_keyword("repeatable");
+
+        if repeatable {
+            self.bump()?;
+        }
+
+        self.expect_keyword("on")?;
+        self.eat(Token::Pipe)?;
+
+        let mut locations = Vec::new_in(self.arena);
+
+        loop {
+            locations.push(self.name("a directive location")?);
+
+            if !self.eat(Token::Pipe)? {
+                break;
+            }
+        }
+
+        Ok(Definition::Directive(DirectiveDefinition {
+            description,
+            name,
+            arguments,
+            repeatable,
+            locations,
+            span: self.span_from(start),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bumpalo::Bump;
+    use indoc::indoc;
+
+    use super::{ParseOptions, parse, parse_selections};
+    use crate::ast::{Definition, Nullability, Selection, Type, TypeKind, Value};
+    use crate::{Dialect, Extension, MinifyError};
+
+    fn parse_err(source: &str) -> MinifyError {
+        parse(source, &Bump::new(), &ParseOptions::default()).unwrap_err()
+    }
+
+    #[test]
+    fn parses_executable_documents() {
+        let arena = Bump::new();
+        let source = indoc! {r#"
+            query Q($id: ID! = "1", $sizes: [Int!]) @live {
+              alias: image(id: $id, sizes: $sizes, filter: { kind: PNG, tags: ["a"] }) {
+                ...img @include(if: true)
+                ... on Image { url }
+                ... @skip(if: false) { id }
+              }
+            }
+
+            fragment img on Image { id }
+        "#};
+
+        let document = parse(source, &arena, &ParseOptions::default()).unwrap();
+        let [Definition::Operation(query), Definition::Fragment(fragment)] =
+            &document.definitions[..]
+        else {
+            panic!("unexpected definitions: {document:?}");
+        };
+
+        assert_eq!(query.name.as_ref().unwrap().value, "Q");
+        assert_eq!(query.variable_definitions.len(), 2);
+        assert!(matches!(
+            query.variable_definitions[0].ty,
+            Type::NonNull(..)
+        ));
+        assert_eq!(query.directives[0].name.value, "live");
+
+        let Selection::Field(image) = &query.selection_set.selections[0] else {
+            panic!("not a field");
+        };
+        assert_eq!(image.alias.as_ref().unwrap().value, "alias");
+        assert_eq!(image.name.value, "image");
+        assert!(matches!(image.arguments[2].value, Value::Object(..)));
+
+        let selections = &image.selection_set.as_ref().unwrap().selections;
+        assert!(matches!(selections[0], Selection::FragmentSpread(_)));
+        assert!(matches!(selections[1], Selection::InlineFragment(_)));
+        assert!(matches!(selections[2], Selection::InlineFragment(_)));
+
+        assert_eq!(fragment.name.value, "img");
+        assert_eq!(fragment.type_condition.value, "Image");
+        assert_eq!(
+            &source[fragment.span.clone()],
+            "fragment img on Image { id }"
+        );
+    }
+
+    #[test]
+    fn parses_type_system_documents() {
+        let arena = Bump::new();
+        let source = indoc! {r#"
+            schema { query: Query }
+            """Image"""
+            type Image implements Node & Entity @key(fields: "id") {
+              "URL"
+              url(size: Int = 100): String!
+            }
+            union Media = | Image | Video
+            enum Format { PNG JPEG }
+            input Filter { format: Format }
+            scalar Date
+            directive @key(fields: String!) repeatable on OBJECT | INTERFACE
+            extend type Image { id: ID! }
+            extend schema @link
+        "#};
+
+        let document = parse(source, &arena, &ParseOptions::default()).unwrap();
+        assert_eq!(document.definitions.len(), 9);
+
+        let Definition::Type(image) = &document.definitions[1] else {
+            panic!("not a type");
+        };
+        assert!(image.description.as_ref().unwrap().block);
+        let TypeKind::Object { interfaces, fields } = &image.kind else {
+            panic!("not an object type");
+        };
+        assert_eq!(interfaces.len(), 2);
+        assert_eq!(fields[0].arguments[0].name.value, "size");
+        assert_eq!(fields[0].ty.named().value, "String");
+
+        assert!(matches!(document.definitions[7], Definition::Type(ref ty) if ty.extension));
+    }
+
+    #[test]
+    fn parses_selections() {
+        let arena = Bump::new();
+        let selections =
+            parse_selections("id image { url } ...img", &arena, &ParseOptions::default()).unwrap();
+
+        assert_eq!(selections.len(), 3);
+        assert_eq!(selections[1].span(), &(3..16));
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        assert_eq!(
+            parse_err("{ a { b }"),
+            MinifyError::SyntaxError("a selection set", 9..9)
+        );
+        assert_eq!(
+            parse_err("query Q"),
+            MinifyError::SyntaxError("a selection set", 7..7)
+        );
+        assert_eq!(
+            parse_err("{ a ... }"),
+            MinifyError::SyntaxError("a selection set", 8..9)
+        );
+        assert_eq!(
+            parse_err("fragment on on T { a }"),
+            MinifyError::SyntaxError("a fragment name", 9..11)
+        );
+        assert_eq!(
+            parse_err("{ a {} }"),
+            MinifyError::SyntaxError("a selection set", 5..6)
+        );
+        assert_eq!(
+            parse_err("query ($a: Int = $b) { a }"),
+            MinifyError::SyntaxError("a constant value", 17..19)
+        );
+        assert_eq!(
+            parse_err("{ a } }"),
+            MinifyError::SyntaxError("a definition", 6..7)
+        );
+        assert_eq!(
+            parse_err(""),
+            MinifyError::SyntaxError("a definition", 0..0)
+        );
+        assert_eq!(
+            parse_err("{ a % }"),
+            MinifyError::UnexpectedCharacter('%', 4..5)
+        );
+    }
+
+    #[test]
+    fn parses_extensions_of_dialect() {
+        let arena = Bump::new();
+        let source = r#""Image" fragment img($size: Int) on Image { url(size: $size)! tags[?]! ...thumb(size: 1) }"#;
+        let experimental = ParseOptions {
+            dialect: Dialect::EXPERIMENTAL,
+            ..ParseOptions::default()
+        };
+
+        assert_eq!(
+            parse_err(source),
+            MinifyError::UnsupportedSyntax(Extension::OperationDescriptions, 0..7)
+        );
+
+        let document = parse(source, &arena, &experimental).unwrap();
+        let Definition::Fragment(fragment) = &document.definitions[0] else {
+            panic!("not a fragment");
+        };
+        assert_eq!(fragment.variable_definitions.len(), 1);
+
+        let Selection::Field(tags) = &fragment.selection_set.selections[1] else {
+            panic!("not a field");
+        };
+        assert!(matches!(
+            tags.nullability,
+            Some(Nullability::List {
+                element: Some(Nullability::Optional(_)),
+                designator: Some(Nullability::Required(_)),
+                ..
+            })
+        ));
+        assert!(matches!(
+            &fragment.selection_set.selections[2],
+            Selection::FragmentSpread(spread) if spread.arguments.len() == 1
+        ));
+    }
+
+    #[test]
+    fn rejects_deep_nesting() {
+        const DEPTH: usize = 100_000;
+
+        let options = ParseOptions {
+            dialect: Dialect::EXPERIMENTAL,
+            ..ParseOptions::default()
+        };
+        let nested = |open: &str, inner: &str, close: &str, depth: usize| {
+            format!("{}{inner}{}", open.repeat(depth), close.repeat(depth))
+        };
+        let too_deep = |source: &str| {
+            matches!(
+                parse(source, &Bump::new(), &options),
+                Err(MinifyError::SyntaxError(super::TOO_DEEP, _))
+            )
+        };
+
+        for (prefix, open, inner, close, suffix) in [
+            ("{ a(x: ", "[", "1", "]", ") }"),
+            ("{ a(x: ", "{ x: ", "1", " }", ") }"),
+            ("query($a: ", "[", "Int", "]", ") { a }"),
+            ("{ ", "a { ", "b", " }", " }"),
+            ("{ a", "[", "?", "]", " }"),
+        ] {
+            let deep = format!("{prefix}{}{suffix}", nested(open, inner, close, DEPTH));
+            let shallow = format!("{prefix}{}{suffix}", nested(open, inner, close, 100));
+
+            assert!(too_deep(&deep), "{prefix}{open}");
+            assert!(
+                parse(&shallow, &Bump::new(), &options).is_ok(),
+                "{prefix}{open}"
+            );
+        }
+
+        assert!(matches!(
+            parse_selections(&nested("a { ", "b", " }", DEPTH), &Bump::new(), &options),
+            Err(MinifyError::SyntaxError(super::TOO_DEEP, _))
+        ));
+    }
+}
@@ -0,0 +1,314 @@
+//! Type system of a schema, used to validate executable documents
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use bumpalo::Bump;
+
+use crate::ast::{
+    self, Definition, Directive, InputValueDefinition, OperationType, TypeKind, Value,
+};
+use crate::{MinifyError, ParseOptions, parse};
+
+/// Types of a schema built from its SDL
+///
+/// Only what is needed to validate executable documents is kept: fields with their arguments
+/// and types, possible types of abstract types, and root operation types.
+/// Type extensions are merged into the extended types.
+///
+/// # Examples
+///
+/// ```
+/// use graphql_minify::{ParseOptions, Schema};
+///
+/// let schema = Schema::parse("type Query { image(id: ID!): Image } type Image { url: String }", &ParseOptions::default()).unwrap();
+///
+/// assert!(schema.has_type("Image"));
+/// assert!(!schema.has_type("Video"));
+/// ```
+#[derive(Debug, Default)]
+pub struct Schema {
+    types: HashMap<String, SchemaType>,
+    query: Option<String>,
+    mutation: Option<String>,
+    subscription: Option<String>,
+}
+
+#[derive(Debug)]
+pub(crate) struct SchemaType {
+    pub kind: SchemaTypeKind,
+    /// fields of objects and interfaces or input fields of input objects
+    pub fields: HashMap<String, SchemaField>,
+    /// interfaces implemented by objects and interfaces
+    interfaces: Vec<String>,
+    /// members of unions
+    members: Vec<String>,
+    /// values of enums with their deprecation reasons
+    pub enum_values: HashMap<String, Option<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SchemaTypeKind {
+    Scalar,
+    Object,
+    Interface,
+    Union,
+    Enum,
+    InputObject,
+}
+
+impl SchemaTypeKind {
+    /// returns whether the type has a selection set
+    pub const fn is_composite(self) -> bool {
+        matches!(self, Self::Object | Self::Interface | Self::Union)
+    }
+}
+
+/// Field of an object or an interface, or an input field of an input object
+#[derive(Debug)]
+pub(crate) struct SchemaField {
+    pub ty: TypeRef,
+    pub arguments: HashMap<String, SchemaField>,
+    /// whether the argument or the input field has a default value
+    pub has_default: bool,
+    /// reason of the deprecation, if the field is deprecated
+    pub deprecated: Option<String>,
+}
+
+/// Type reference with owned names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TypeRef {
+    Named(String),
+    List(Box<TypeRef>),
+    NonNull(Box<TypeRef>),
+}
+
+impl TypeRef {
+    pub fn named(&self) -> &str {
+        match self {
+            TypeRef::Named(name) => name,
+            TypeRef::List(ty) | TypeRef::NonNull(ty) => ty.named(),
+        }
+    }
+}
+
+impl From<&ast::Type<'_>> for TypeRef {
+    fn from(ty: &ast::Type<'_>) -> Self {
+        match ty {
+            ast::Type::Named(name) => TypeRef::Named(name.value.to_owned()),
+            ast::Type::List(ty, _) => TypeRef::List(Box::new(TypeRef::from(*ty))),
+            ast::Type::NonNull(ty, _) => TypeRef::NonNull(Box::new(TypeRef::from(*ty))),
+        }
+    }
+}
+
+impl fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeRef::Named(name) => f.write_str(name),
+            TypeRef::List(ty) => write!(f, "[{ty}]"),
+            TypeRef::NonNull(ty) => write!(f, "{ty}!"),
+        }
+    }
+}
+
+const BUILT_IN_SCALARS: &[&str] = &["Int", "Float", "String", "Boolean", "ID"];
+
+impl Schema {
+    /// Parses the SDL of a schema.
+    ///
+    /// Executable definitions in the SDL are ignored.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first lexing or syntax error of the SDL.
+    pub fn parse(sdl: &str, options: &ParseOptions) -> Result<Self, MinifyError> {
+        let arena = Bump::new();
+        let document = parse(sdl, &arena, options)?;
+
+        Ok(Self::from_document(&document))
+    }
+
+    /// Builds the schema from type system definitions of the document.
+    pub fn from_document(document: &ast::Document) -> Self {
+        let mut schema = Self::default();
+
+        for scalar in BUILT_IN_SCALARS {
+            schema.types.insert(
+                (*scalar).to_owned(),
+                SchemaType::new(SchemaTypeKind::Scalar),
+            );
+        }
+
+        let mut has_schema_definition = false;
+
+        for definition in &document.definitions {
+            match definition {
+                Definition::Schema(definition) => {
+                    has_schema_definition = true;
+
+                    for root in &definition.operation_types {
+                        let name = Some(root.ty.value.to_owned());
+
+                        match root.operation {
+                            OperationType::Query => schema.query = name,
+                            OperationType::Mutation => schema.mutation = name,
+                            OperationType::Subscription => schema.subscription = name,
+                        }
+                    }
+                }
+                Definition::Type(definition) => schema.add_type(definition),
+                Definition::Operation(_) | Definition::Fragment(_) | Definition::Directive(_) => {}
+            }
+        }
+
+        if !has_schema_definition {
+            let default_root =
+                |schema: &Self, name: &str| schema.has_type(name).then(|| name.to_owned());
+
+            schema.query = default_root(&schema, "Query");
+            schema.mutation = default_root(&schema, "Mutation");
+            schema.subscription = default_root(&schema, "Subscription");
+        }
+
+        schema
+    }
+
+    fn add_type(&mut self, definition: &ast::TypeDefinition) {
+        let kind = match &definition.kind {
+            TypeKind::Scalar => SchemaTypeKind::Scalar,
+            TypeKind::Object { .. } => SchemaTypeKind::Object,
+            TypeKind::Interface { .. } => SchemaTypeKind::Interface,
+            TypeKind::Union { .. } => SchemaTypeKind::Union,
+            TypeKind::Enum { .. } => SchemaTypeKind::Enum,
+            TypeKind::InputObject { .. } => SchemaTypeKind::InputObject,
+        };
+
+        let ty = self
+            .types
+            .entry(definition.name.value.to_owned())
+            .or_insert_with(|| SchemaType::new(kind));
+
+        match &definition.kind {
+            TypeKind::Object { interfaces, fields }
+            | TypeKind::Interface { interfaces, fields } => {
+                ty.interfaces
+                    .extend(interfaces.iter().map(|name| name.value.to_owned()));
+
+                for field in fields {
+                    ty.fields.insert(
+                        field.name.value.to_owned(),
+                        SchemaField {
+                            ty: TypeRef::from(&field.ty),
+                            arguments: input_values(&field.arguments),
+                            has_default: false,
+                            deprecated: deprecation(&field.directives),
+                        },
+                    );
+                }
+            }
+            TypeKind::Union { members } => {
+                ty.members
+                    .extend(members.iter().map(|name| name.value.to_owned()));
+            }
+            TypeKind::Enum { values } => {
+                ty.enum_values.extend(
+                    values
+                        .iter()
+                        .map(|value| (value.name.value.to_owned(), deprecation(&value.directives))),
+                );
+            }
+            TypeKind::InputObject { fields } => ty.fields.extend(input_values(fields)),
+            TypeKind::Scalar => {}
+        }
+    }
+
+    /// Returns whether the schema defines the type, including built-in scalars.
+    pub fn has_type(&self, name: &str) -> bool {
+        self.types.contains_key(name)
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&SchemaType> {
+        self.types.get(name)
+    }
+
+    /// returns the root type of the operation, if the schema supports it
+    pub(crate) fn root(&self, operation: OperationType) -> Option<&str> {
+        match operation {
+            OperationType::Query => self.query.as_deref(),
+            OperationType::Mutation => self.mutation.as_deref(),
+            OperationType::Subscription => self.subscription.as_deref(),
+        }
+    }
+
+    /// returns names of object types that the composite type may resolve to
+    pub(crate) fn possible_types<'s>(&'s self, name: &'s str) -> HashSet<&'s str> {
+        let Some(ty) = self.get(name) else {
+            return HashSet::new();
+        };
+
+        match ty.kind {
+            SchemaTypeKind::Object => HashSet::from([name]),
+            SchemaTypeKind::Union => ty.members.iter().map(String::as_str).collect(),
+            SchemaTypeKind::Interface => self
+                .types
+                .iter()
+                .filter(|(_, other)| {
+                    other.kind == SchemaTypeKind::Object
+                        && other.interfaces.iter().any(|interface| interface == name)
+                })
+                .map(|(other, _)| other.as_str())
+                .collect(),
+            _ => HashSet::new(),
+        }
+    }
+}
+
+impl SchemaType {
+    fn new(kind: SchemaTypeKind) -> Self {
+        Self {
+            kind,
+            fields: HashMap::new(),
+            interfaces: Vec::new(),
+            members: Vec::new(),
+            enum_values: HashMap::new(),
+        }
+    }
+}
+
+fn input_values(values: &[InputValueDefinition]) -> HashMap<String, SchemaField> {
+    values
+        .iter()
+        .map(|value| {
+            (
+                value.name.value.to_owned(),
+                SchemaField {
+                    ty: TypeRef::from(&value.ty),
+                    arguments: HashMap::new(),
+                    has_default: value.default_value.is_some(),
+                    deprecated: deprecation(&value.directives),
+                },
+            )
+        })
+        .collect()
+}
+
+/// returns the reason of the [`@deprecated`](https://spec.graphql.org/October2021/#sec--deprecated) directive, if any
+fn deprecation(directives: &[Directive]) -> Option<String> {
+    const DEFAULT_REASON: &str = "No longer supported";
+
+    let directive = directives
+        .iter()
+        .find(|directive| directive.name.value == "deprecated")?;
+
+    let reason = directive
+        .arguments
+        .iter()
+        .find(|argument| argument.name.value == "reason")
+        .and_then(|argument| match &argument.value {
+            Value::String(reason) => Some(reason.value()),
+            _ => None,
+        });
+
+    Some(reason.unwrap_or_else(|| DEFAULT_REASON.to_owned()))
+}
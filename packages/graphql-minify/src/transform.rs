@@ -0,0 +1,612 @@
+//! Transforms of executable documents applied before minification, e.g. removal of `@client` fields
+//!
+//! Transforms are computed on the AST as [`Edit`]s of the source, so that the rest of the document
+//! is kept as written and can be minified or formatted as usual.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use bumpalo::Bump;
+use logos::{Logos, Span};
+
+use crate::ast::{
+    Definition, Directive, Document, Name, OperationDefinition, Selection, SelectionSet,
+    VariableDefinition,
+};
+use crate::edit::{Edit, apply_edits};
+use crate::lexer::Token;
+use crate::usages::Usages;
+use crate::{MinifyError, ParseOptions, parse};
+
+/// Transforms of [`transform`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TransformOptions {
+    /// remove fields marked `@client` with their selection sets, as well as fields, fragments
+    /// and operations whose selection sets are left empty, and variables and fragments used only
+    /// by removed fields
+    pub remove_client_fields: bool,
+    /// add `__typename` to every selection set except the roots of operations, unless it is selected
+    /// without an alias already, as done by `addTypename` of Apollo Client at runtime
+    pub add_typename: bool,
+    /// name given to the anonymous operation, if it is the only operation of the document
+    pub operation_name: Option<String>,
+}
+
+impl TransformOptions {
+    /// returns whether any transform is enabled
+    pub const fn is_enabled(&self) -> bool {
+        self.remove_client_fields || self.add_typename || self.operation_name.is_some()
+    }
+}
+
+/// Parses the document and applies the enabled transforms.
+///
+/// # Examples
+///
+/// ```
+/// use graphql_minify::{ParseOptions, TransformOptions, transform};
+///
+/// let options = TransformOptions { remove_client_fields: true, ..TransformOptions::default() };
+/// let transformed = transform("{ user { id isLoggedIn @client } }", &options, &ParseOptions::default()).unwrap();
+///
+/// assert_eq!(transformed, "{ user { id  } }");
+/// ```
+///
+/// # Errors
+///
+/// This function will return the first lexing or syntax error of the document.
+pub fn transform(
+    source: &str,
+    options: &TransformOptions,
+    parse_options: &ParseOptions,
+) -> Result<String, MinifyError> {
+    let arena = Bump::new();
+    let document = parse(source, &arena, parse_options)?;
+
+    Ok(apply_edits(
+        source,
+        &transform_edits(source, &document, options),
+    ))
+}
+
+/// Returns the edits of the enabled transforms, sorted by their positions and not overlapping.
+///
+/// Edits inside removed parts of the document are dropped.
+pub fn transform_edits(source: &str, document: &Document, options: &TransformOptions) -> Vec<Edit> {
+    let mut edits = Vec::new();
+
+    if options.remove_client_fields {
+        edits.extend(ClientFields::new(document).edits(source, document));
+    }
+
+    if options.add_typename {
+        edits.extend(typename_edits(document));
+    }
+
+    if let Some(name) = &options.operation_name {
+        edits.extend(name_edit(source, document, name));
+    }
+
+    // deletions go before insertions at their starts, which are dropped as inside them
+    edits.sort_by_key(|edit| (edit.span.start, Reverse(edit.span.end)));
+
+    let mut end = 0;
+
+    edits.retain(|edit| {
+        let is_kept = edit.span.start >= end;
+
+        if is_kept {
+            end = edit.span.end;
+        }
+
+        is_kept
+    });
+
+    edits
+}
+
+/// Removal of fields marked `@client`
+struct ClientFields<'a> {
+    /// fragments whose selection sets are left empty, spreads of which are removed as well
+    empty: HashSet<&'a str>,
+}
+
+impl<'a> ClientFields<'a> {
+    fn new(document: &Document<'a>) -> Self {
+        let fragments = document
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Fragment(fragment) => Some(fragment),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut transform = Self {
+            empty: HashSet::new(),
+        };
+
+        // fragments may be emptied by spreads of other emptied fragments
+        loop {
+            let emptied = fragments
+                .iter()
+                .filter(|fragment| !transform.empty.contains(fragment.name.value))
+                .filter(|fragment| transform.selection_set(&fragment.selection_set).is_none())
+                .map(|fragment| fragment.name.value)
+                .collect::<Vec<_>>();
+
+            if emptied.is_empty() {
+                break transform;
+            }
+
+            transform.empty.extend(emptied);
+        }
+    }
+
+    fn edits(&self, source: &str, document: &Document) -> Vec<Edit> {
+        let mut edits = Vec::new();
+
+        for definition in &document.definitions {
+            let selection_set = match definition {
+                Definition::Operation(operation) => &operation.selection_set,
+                Definition::Fragment(fragment) => &fragment.selection_set,
+                Definition::Schema(_) | Definition::Type(_) | Definition::Directive(_) => continue,
+            };
+
+            match self.selection_set(selection_set) {
+                Some(selection_edits) => edits.extend(selection_edits),
+                None => edits.push(Edit::delete(definition.span())),
+            }
+        }
+
+        let unused = unused_edits(source, document, &edits);
+        edits.extend(unused);
+        edits
+    }
+
+    /// returns edits of the selection set, or [`None`] if all its selections are removed
+    fn selection_set(&self, selection_set: &SelectionSet) -> Option<Vec<Edit>> {
+        let mut edits = Vec::new();
+        let mut is_empty = true;
+
+        for selection in &selection_set.selections {
+            let kept = match selection {
+                Selection::Field(field) if is_client(&field.directives) => None,
+                Selection::Field(field) => match &field.selection_set {
+                    Some(selection_set) => self.selection_set(selection_set),
+                    None => Some(Vec::new()),
+                },
+                Selection::FragmentSpread(spread) => {
+                    (!self.empty.contains(spread.name.value)).then(Vec::new)
+                }
+                Selection::InlineFragment(fragment) => self.selection_set(&fragment.selection_set),
+            };
+
+            match kept {
+                Some(selection_edits) => {
+                    is_empty = false;
+                    edits.extend(selection_edits);
+                }
+                None => edits.push(Edit::delete(selection.span())),
+            }
+        }
+
+        (!is_empty).then_some(edits)
+    }
+}
+
+/// Deletions of variable definitions and fragments that were used only by the `removed` parts
+/// of the document, following spreads of fragments
+///
+/// Variables and fragments that were not used before are kept, since they may be used
+/// by other documents or by fragments interpolated into the document.
+fn unused_edits(source: &str, document: &Document, removed: &[Edit]) -> Vec<Edit> {
+    let is_kept = |span: &Span| {
+        !removed
+            .iter()
+            .any(|edit| edit.span.start <= span.start && span.end <= edit.span.end)
+    };
+
+    let mut fragments = HashMap::new();
+    let mut kept_fragments = HashMap::new();
+    let mut spread = HashSet::new();
+
+    for definition in &document.definitions {
+        let usages = match definition {
+            Definition::Operation(operation) => Usages::of_operation(operation),
+            Definition::Fragment(fragment) => {
+                if is_kept(&fragment.span) {
+                    let mut usages = Usages::of_fragment(fragment);
+                    usages.retain(|name| is_kept(&name.span));
+                    kept_fragments.insert(fragment.name.value, usages);
+                }
+
+                let usages = Usages::of_fragment(fragment);
+                spread.extend(usages.spreads.iter().map(|name| name.value));
+                fragments.insert(fragment.name.value, usages);
+                continue;
+            }
+            Definition::Schema(_) | Definition::Type(_) | Definition::Directive(_) => continue,
+        };
+
+        spread.extend(usages.spreads.iter().map(|name| name.value));
+    }
+
+    let mut edits = Vec::new();
+    let mut used = HashSet::new();
+
+    for definition in &document.definitions {
+        match definition {
+            Definition::Operation(operation) if is_kept(&operation.span) => {
+                let (before, _) = Usages::of_operation(operation).transitive(&fragments);
+                let mut usages = Usages::of_operation(operation);
+                usages.retain(|name| is_kept(&name.span));
+                let (after, _) = usages.transitive(&kept_fragments);
+
+                used.extend(usages.spread_fragments(&kept_fragments));
+                edits.extend(variable_edits(source, operation, &before, &after));
+            }
+            // fragments that are not spread in the document are used by other documents
+            Definition::Fragment(fragment) if !spread.contains(fragment.name.value) => {
+                if let Some(usages) = kept_fragments.get(fragment.name.value) {
+                    used.insert(fragment.name.value);
+                    used.extend(usages.spread_fragments(&kept_fragments));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for definition in &document.definitions {
+        if let Definition::Fragment(fragment) = definition
+            && kept_fragments.contains_key(fragment.name.value)
+            && !used.contains(fragment.name.value)
+        {
+            edits.push(Edit::delete(&fragment.span));
+        }
+    }
+
+    edits
+}
+
+/// Deletions of variable definitions of the `operation` that are used `before` the removal of fields,
+/// but not `after`, with the parentheses if no definition is left
+fn variable_edits(
+    source: &str,
+    operation: &OperationDefinition,
+    before: &[&Name],
+    after: &[&Name],
+) -> Vec<Edit> {
+    let is_used = |variables: &[&Name], definition: &VariableDefinition| {
+        variables
+            .iter()
+            .any(|variable| variable.value == definition.variable.value)
+    };
+
+    let unused = operation
+        .variable_definitions
+        .iter()
+        .filter(|definition| is_used(before, definition) && !is_used(after, definition))
+        .map(|definition| Edit::delete(&definition.span))
+        .collect::<Vec<_>>();
+
+    if unused.is_empty() || unused.len() < operation.variable_definitions.len() {
+        return unused;
+    }
+
+    variables_span(source, operation)
+        .map(|span| Edit::delete(&span))
+        .into_iter()
+        .collect()
+}
+
+/// returns the span of the variable definitions of the operation with their parentheses
+///
+/// The parentheses are not kept in the AST, so the source around the definitions is lexed
+fn variables_span(source: &str, operation: &OperationDefinition) -> Option<Span> {
+    let first = operation.variable_definitions.first()?;
+    let last = operation.variable_definitions.last()?;
+
+    // only the keyword and the name precede the opening parenthesis
+    let start = operation
+        .description
+        .as_ref()
+        .map_or(operation.span.start, |description| description.span.end);
+    let mut lexer = Token::lexer(&source[start..first.span.start]);
+    let mut open = None;
+
+    while let Some(token) = lexer.next() {
+        if token == Ok(Token::ParenOpen) {
+            open = Some(start + lexer.span().start);
+        }
+    }
+
+    let mut lexer = Token::lexer(&source[last.span.end..]);
+
+    match lexer.next() {
+        Some(Ok(Token::ParenClose)) => Some(open?..last.span.end + lexer.span().end),
+        _ => None,
+    }
+}
+
+/// Insertion of `__typename` into selection sets below the roots of operations
+fn typename_edits(document: &Document) -> Vec<Edit> {
+    let mut edits = Vec::new();
+
+    for definition in &document.definitions {
+        match definition {
+            Definition::Operation(operation) => {
+                add_typename_below_root(&operation.selection_set, &mut edits);
+            }
+            Definition::Fragment(fragment) => add_typename(&fragment.selection_set, &mut edits),
+            Definition::Schema(_) | Definition::Type(_) | Definition::Directive(_) => {}
+        }
+    }
+
+    edits
+}
+
+fn add_typename(selection_set: &SelectionSet, edits: &mut Vec<Edit>) {
+    let has_typename = selection_set.selections.iter().any(|selection| {
+        matches!(selection, Selection::Field(field) if field.alias.is_none() && field.name.value == "__typename")
+    });
+
+    // appended as by Apollo Client, which also keeps insertions out of removed selections
+    if !has_typename {
+        edits.push(Edit::insert(selection_set.span.end - 1, " __typename"));
+    }
+
+    for selection in &selection_set.selections {
+        match selection {
+            Selection::Field(field) => {
+                if let Some(selection_set) = &field.selection_set {
+                    add_typename(selection_set, edits);
+                }
+            }
+            Selection::InlineFragment(fragment) => add_typename(&fragment.selection_set, edits),
+            Selection::FragmentSpread(_) => {}
+        }
+    }
+}
+
+/// inline fragments of the root are skipped as well, since subscriptions must select a single field
+fn add_typename_below_root(selection_set: &SelectionSet, edits: &mut Vec<Edit>) {
+    for selection in &selection_set.selections {
+        match selection {
+            Selection::Field(field) => {
+                if let Some(selection_set) = &field.selection_set {
+                    add_typename(selection_set, edits);
+                }
+            }
+            Selection::InlineFragment(fragment) => {
+                add_typename_below_root(&fragment.selection_set, edits);
+            }
+            Selection::FragmentSpread(_) => {}
+        }
+    }
+}
+
+/// Naming of the only operation of the document, if it is anonymous
+fn name_edit(source: &str, document: &Document, name: &str) -> Option<Edit> {
+    let mut operations = document
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Operation(operation) => Some(operation),
+            _ => None,
+        });
+
+    let operation = operations.next()?;
+
+    if operation.name.is_some() || operations.next().is_some() {
+        return None;
+    }
+
+    Some(match keyword_end(source, operation) {
+        Some(end) => Edit::insert(end, &format!(" {name}")),
+        None => Edit::insert(operation.selection_set.span.start, &format!("query {name}")),
+    })
+}
+
+/// returns the end of the operation keyword, or [`None`] for the query shorthand
+///
+/// The keyword is not kept in the AST, so the source after the description is lexed
+fn keyword_end(source: &str, operation: &OperationDefinition) -> Option<usize> {
+    let start = operation
+        .description
+        .as_ref()
+        .map_or(operation.span.start, |description| description.span.end);
+    let mut lexer = Token::lexer(&source[start..]);
+
+    match lexer.next() {
+        Some(Ok(Token::Identifier)) => Some(start + lexer.span().end),
+        _ => None,
+    }
+}
+
+fn is_client(directives: &[Directive]) -> bool {
+    directives
+        .iter()
+        .any(|directive| directive.name.value == "client")
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::{TransformOptions, transform};
+    use crate::{MinifyAllocator, ParseOptions, minify};
+
+    fn transform_and_minify(source: &str, options: &TransformOptions) -> String {
+        let transformed = transform(source, options, &ParseOptions::default()).unwrap();
+
+        minify(transformed, &mut MinifyAllocator::default()).unwrap()
+    }
+
+    fn remove_client_fields(source: &str) -> String {
+        let options = TransformOptions {
+            remove_client_fields: true,
+            ..TransformOptions::default()
+        };
+
+        transform_and_minify(source, &options)
+    }
+
+    fn add_typename(source: &str) -> String {
+        let options = TransformOptions {
+            add_typename: true,
+            ..TransformOptions::default()
+        };
+
+        transform_and_minify(source, &options)
+    }
+
+    #[test]
+    fn removes_client_fields() {
+        let source = indoc! {r"
+            query User($id: ID!) {
+              user(id: $id) {
+                id
+                settings @client { theme locale }
+                ... on Admin { isImpersonating @client }
+                ...local
+                ...profile
+              }
+              cart @client { items }
+            }
+
+            fragment local on User { isLoggedIn @client }
+            fragment profile on User { name ...local }
+        "};
+
+        assert_eq!(
+            remove_client_fields(source),
+            "query User($id:ID!){user(id:$id){id...profile}}fragment profile on User{name}"
+        );
+    }
+
+    #[test]
+    fn removes_unused_variables() {
+        assert_eq!(
+            remove_client_fields("query Q($t: String) { a { id b(t: $t) @client } }"),
+            "query Q{a{id}}"
+        );
+        assert_eq!(
+            remove_client_fields(
+                "query Q($a: Int, $t: String, $u: ID) { a(a: $a) { id b(t: $t) @client } }"
+            ),
+            "query Q($a:Int$u:ID){a(a:$a){id}}"
+        );
+        assert_eq!(
+            remove_client_fields(indoc! {r"
+                query Q($t: String) { a { ...F } }
+                fragment F on A { id b(t: $t) @client }
+            "}),
+            "query Q{a{...F}}fragment F on A{id}"
+        );
+    }
+
+    #[test]
+    fn removes_unused_fragments() {
+        assert_eq!(
+            remove_client_fields("{ a { id s @client { ...S } } } fragment S on T { x }"),
+            "{a{id}}"
+        );
+        assert_eq!(
+            remove_client_fields(indoc! {r"
+                fragment S on T { x ...U y @client { ...V } }
+                fragment U on T { u }
+                fragment V on T { v }
+            "}),
+            "fragment S on T{x...U}fragment U on T{u}"
+        );
+    }
+
+    #[test]
+    fn removes_emptied_definitions() {
+        let source = indoc! {r"
+            query Local { isLoggedIn @client }
+            query Cart { cart { items @client } }
+            query Remote { user { id } }
+        "};
+
+        assert_eq!(remove_client_fields(source), "query Remote{user{id}}");
+    }
+
+    #[test]
+    fn adds_typename() {
+        let source = indoc! {r"
+            subscription Feed {
+              ... on Subscription { post { id author { __typename name } } }
+            }
+
+            query User {
+              user { kind: __typename ...profile ... on Admin { role } }
+            }
+
+            fragment profile on User { name }
+        "};
+
+        assert_eq!(
+            add_typename(source),
+            "subscription Feed{...on Subscription{post{id author{__typename name}__typename}}}\
+             query User{user{kind:__typename...profile...on Admin{role __typename}__typename}}\
+             fragment profile on User{name __typename}"
+        );
+    }
+
+    #[test]
+    fn adds_typename_to_kept_selection_sets() {
+        let options = TransformOptions {
+            remove_client_fields: true,
+            add_typename: true,
+            ..TransformOptions::default()
+        };
+        let source = "{ user { id settings @client { theme } } cart { items @client } }";
+
+        assert_eq!(
+            transform_and_minify(source, &options),
+            "{user{id __typename}}"
+        );
+    }
+
+    #[test]
+    fn names_anonymous_operation() {
+        let options = TransformOptions {
+            operation_name: Some("GetUser".to_owned()),
+            ..TransformOptions::default()
+        };
+
+        for (source, expected) in [
+            ("{ user { id } }", "query GetUser{user{id}}"),
+            ("query { user { id } }", "query GetUser{user{id}}"),
+            (
+                "query($id: ID) @live { user(id: $id) { id } }",
+                "query GetUser($id:ID)@live{user(id:$id){id}}",
+            ),
+            (
+                "subscription # comment\n { user { id } }",
+                "subscription GetUser{user{id}}",
+            ),
+            ("query User { user { id } }", "query User{user{id}}"),
+            (
+                "{ user { ...user } } fragment user on User { id }",
+                "query GetUser{user{...user}}fragment user on User{id}",
+            ),
+            ("{ a } { b }", "{a}{b}"),
+        ] {
+            assert_eq!(transform_and_minify(source, &options), expected);
+        }
+    }
+
+    #[test]
+    fn names_kept_operation_only() {
+        let options = TransformOptions {
+            remove_client_fields: true,
+            operation_name: Some("GetUser".to_owned()),
+            ..TransformOptions::default()
+        };
+
+        assert_eq!(transform_and_minify("{ isLoggedIn @client }", &options), "");
+    }
+}
@@ -0,0 +1,131 @@
+//! Variables and fragments used by executable definitions
+
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{
+    Argument, Directive, FragmentDefinition, Name, OperationDefinition, Selection, SelectionSet,
+    Value,
+};
+
+/// Variables and fragments used directly in a definition
+#[derive(Default)]
+pub(crate) struct Usages<'d, 'a> {
+    pub variables: Vec<&'d Name<'a>>,
+    pub spreads: Vec<&'d Name<'a>>,
+}
+
+impl<'d, 'a> Usages<'d, 'a> {
+    pub fn of_operation(operation: &'d OperationDefinition<'a>) -> Self {
+        let mut usages = Self::default();
+
+        usages.directives(&operation.directives);
+        usages.selection_set(&operation.selection_set);
+        usages
+    }
+
+    /// variables defined by fragment arguments are local to the fragment and are not listed
+    pub fn of_fragment(fragment: &'d FragmentDefinition<'a>) -> Self {
+        let mut usages = Self::default();
+
+        usages.directives(&fragment.directives);
+        usages.selection_set(&fragment.selection_set);
+        usages.variables.retain(|variable| {
+            !fragment
+                .variable_definitions
+                .iter()
+                .any(|definition| definition.variable.value == variable.value)
+        });
+        usages
+    }
+
+    /// keeps only variables and spreads whose names satisfy the predicate
+    pub fn retain(&mut self, is_kept: impl Fn(&Name) -> bool) {
+        self.variables.retain(|variable| is_kept(variable));
+        self.spreads.retain(|spread| is_kept(spread));
+    }
+
+    /// returns names of fragments spread directly and in spread fragments, given their `fragments` usages
+    pub fn spread_fragments(&self, fragments: &HashMap<&'a str, Self>) -> HashSet<&'a str> {
+        let mut visited = HashSet::new();
+        let mut pending = self.spreads.clone();
+
+        while let Some(spread) = pending.pop() {
+            if visited.insert(spread.value)
+                && let Some(usages) = fragments.get(spread.value)
+            {
+                pending.extend(&usages.spreads);
+            }
+        }
+
+        visited
+    }
+
+    /// returns variables used directly and in spread fragments, given their `fragments` usages,
+    /// and whether all spread fragments are defined
+    pub fn transitive(&self, fragments: &HashMap<&'a str, Self>) -> (Vec<&'d Name<'a>>, bool) {
+        let mut variables = self.variables.clone();
+        let mut is_complete = true;
+
+        for name in self.spread_fragments(fragments) {
+            match fragments.get(name) {
+                Some(usages) => variables.extend(&usages.variables),
+                None => is_complete = false,
+            }
+        }
+
+        (variables, is_complete)
+    }
+
+    fn selection_set(&mut self, selection_set: &'d SelectionSet<'a>) {
+        for selection in &selection_set.selections {
+            match selection {
+                Selection::Field(field) => {
+                    self.arguments(&field.arguments);
+                    self.directives(&field.directives);
+
+                    if let Some(selection_set) = &field.selection_set {
+                        self.selection_set(selection_set);
+                    }
+                }
+                Selection::FragmentSpread(spread) => {
+                    self.spreads.push(&spread.name);
+                    self.arguments(&spread.arguments);
+                    self.directives(&spread.directives);
+                }
+                Selection::InlineFragment(fragment) => {
+                    self.directives(&fragment.directives);
+                    self.selection_set(&fragment.selection_set);
+                }
+            }
+        }
+    }
+
+    fn directives(&mut self, directives: &'d [Directive<'a>]) {
+        for directive in directives {
+            self.arguments(&directive.arguments);
+        }
+    }
+
+    fn arguments(&mut self, arguments: &'d [Argument<'a>]) {
+        for argument in arguments {
+            self.value(&argument.value);
+        }
+    }
+
+    fn value(&mut self, value: &'d Value<'a>) {
+        match value {
+            Value::Variable(name) => self.variables.push(name),
+            Value::List(items, _) => {
+                for item in items {
+                    self.value(item);
+                }
+            }
+            Value::Object(fields, _) => {
+                for field in fields {
+                    self.value(&field.value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
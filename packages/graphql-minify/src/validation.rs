@@ -0,0 +1,813 @@
+//! Validation of executable documents against a [`Schema`]
+
+use std::collections::HashMap;
+use std::fmt;
+
+use logos::Span;
+
+use crate::ast::{
+    Argument, Definition, Document, Field, FragmentDefinition, Name, OperationDefinition,
+    OperationType, Selection, SelectionSet, Value, VariableDefinition,
+};
+use crate::codes::{self, ErrorCode};
+use crate::schema::{Schema, SchemaField, SchemaTypeKind, TypeRef};
+
+/// A mismatch between an executable document and the [`Schema`]
+///
+/// Spans are byte ranges in the validated document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// field that is not defined on the type of the selection set
+    UnknownField {
+        field: String,
+        parent: String,
+        span: Span,
+    },
+    /// type that is not defined in the schema, including a missing root operation type
+    UnknownType { name: String, span: Span },
+    /// argument that is not defined on the field
+    UnknownArgument {
+        argument: String,
+        field: String,
+        span: Span,
+    },
+    /// type condition on a type that is not composite or can never be the type of the selection set
+    InvalidTypeCondition {
+        condition: String,
+        /// type of the selection set, `None` if the condition is not a composite type
+        parent: Option<String>,
+        span: Span,
+    },
+    /// variable used in a position whose type it is not compatible with
+    VariableTypeMismatch {
+        variable: String,
+        found: String,
+        expected: String,
+        span: Span,
+    },
+}
+
+impl ValidationError {
+    pub const fn as_str(&self) -> &str {
+        self.error_code().message
+    }
+
+    /// returns the stable code of the error, e.g. `GQLMIN010`
+    pub const fn code(&self) -> &'static str {
+        self.error_code().code
+    }
+
+    /// returns the description of the error from the [`codes`] catalogue
+    pub const fn error_code(&self) -> &'static ErrorCode {
+        match self {
+            ValidationError::UnknownField { .. } => &codes::UNKNOWN_FIELD,
+            ValidationError::UnknownType { .. } => &codes::UNKNOWN_TYPE,
+            ValidationError::UnknownArgument { .. } => &codes::UNKNOWN_ARGUMENT,
+            ValidationError::InvalidTypeCondition { .. } => &codes::INVALID_TYPE_CONDITION,
+            ValidationError::VariableTypeMismatch { .. } => &codes::VARIABLE_TYPE_MISMATCH,
+        }
+    }
+
+    pub const fn span(&self) -> &Span {
+        match self {
+            ValidationError::UnknownField { span, .. }
+            | ValidationError::UnknownType { span, .. }
+            | ValidationError::UnknownArgument { span, .. }
+            | ValidationError::InvalidTypeCondition { span, .. }
+            | ValidationError::VariableTypeMismatch { span, .. } => span,
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::UnknownField { field, parent, .. } => {
+                write!(f, "unknown field `{field}` on type `{parent}`")
+            }
+            ValidationError::UnknownType { name, .. } => write!(f, "unknown type `{name}`"),
+            ValidationError::UnknownArgument {
+                argument, field, ..
+            } => write!(f, "unknown argument `{argument}` of field `{field}`"),
+            ValidationError::InvalidTypeCondition {
+                condition,
+                parent: Some(parent),
+                ..
+            } => write!(
+                f,
+                "fragment on `{condition}` can never apply to type `{parent}`"
+            ),
+            ValidationError::InvalidTypeCondition {
+                condition,
+                parent: None,
+                ..
+            } => write!(f, "fragment on non-composite type `{condition}`"),
+            ValidationError::VariableTypeMismatch {
+                variable,
+                found,
+                expected,
+                ..
+            } => write!(
+                f,
+                "variable `${variable}` of type `{found}` is used where `{expected}` is expected"
+            ),
+        }
+    }
+}
+
+impl Schema {
+    /// Validates operations and fragments of the document against the schema.
+    ///
+    /// The checks are: unknown fields and types, unknown arguments of fields,
+    /// type conditions of fragments that can never apply, and variables used in positions
+    /// of incompatible types. Type system definitions and spreads of fragments
+    /// that are not defined in the document are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use graphql_minify::{Bump, ParseOptions, Schema, ValidationError, parse};
+    ///
+    /// let options = ParseOptions::default();
+    /// let schema = Schema::parse("type Query { image(id: ID!): Image } type Image { url: String }", &options).unwrap();
+    ///
+    /// let arena = Bump::new();
+    /// let document = parse("query ($id: ID!) { image(id: $id) { url size } }", &arena, &options).unwrap();
+    ///
+    /// assert_eq!(
+    ///     schema.validate(&document),
+    ///     [ValidationError::UnknownField { field: "size".into(), parent: "Image".into(), span: 40..44 }],
+    /// );
+    /// ```
+    pub fn validate(&self, document: &Document) -> Vec<ValidationError> {
+        self.walk(document).errors
+    }
+
+    /// Lists usages of fields, arguments, input fields and enum values that are marked
+    /// [`@deprecated`](https://spec.graphql.org/October2021/#sec--deprecated) in the schema.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use graphql_minify::{Bump, ParseOptions, Schema, parse};
+    ///
+    /// let options = ParseOptions::default();
+    /// let schema = Schema::parse(r#"type Query { image: String @deprecated(reason: "Use `media`") }"#, &options).unwrap();
+    ///
+    /// let arena = Bump::new();
+    /// let document = parse("{ image }", &arena, &options).unwrap();
+    /// let deprecations = schema.deprecations(&document);
+    ///
+    /// assert_eq!(deprecations[0].to_string(), "field `Query.image` is deprecated: Use `media`");
+    /// assert_eq!(deprecations[0].span, 2..7);
+    /// ```
+    pub fn deprecations(&self, document: &Document) -> Vec<Deprecation> {
+        self.walk(document).deprecations
+    }
+
+    /// Validates the document and lists its deprecated usages in a single walk,
+    /// same as [`Schema::validate`] and [`Schema::deprecations`] together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use graphql_minify::{Bump, ParseOptions, Schema, parse};
+    ///
+    /// let options = ParseOptions::default();
+    /// let schema = Schema::parse(r#"type Query { image: String @deprecated(reason: "Use `media`") }"#, &options).unwrap();
+    ///
+    /// let arena = Bump::new();
+    /// let document = parse("{ image video }", &arena, &options).unwrap();
+    /// let (errors, deprecations) = schema.check(&document);
+    ///
+    /// assert_eq!(errors, schema.validate(&document));
+    /// assert_eq!(deprecations, schema.deprecations(&document));
+    /// ```
+    pub fn check(&self, document: &Document) -> (Vec<ValidationError>, Vec<Deprecation>) {
+        let validator = self.walk(document);
+        (validator.errors, validator.deprecations)
+    }
+
+    fn walk<'d, 'a>(&self, document: &'d Document<'a>) -> Validator<'_, 'd, 'a> {
+        let fragments = document
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Fragment(fragment) => Some((fragment.name.value, fragment)),
+                _ => None,
+            })
+            .collect();
+
+        let mut validator = Validator {
+            schema: self,
+            fragments,
+            variables: HashMap::new(),
+            errors: Vec::new(),
+            deprecations: Vec::new(),
+        };
+
+        for definition in &document.definitions {
+            match definition {
+                Definition::Operation(operation) => validator.operation(operation),
+                Definition::Fragment(fragment) => validator.fragment(fragment),
+                Definition::Schema(_) | Definition::Type(_) | Definition::Directive(_) => {}
+            }
+        }
+
+        validator
+    }
+}
+
+/// Usage of a schema element marked `@deprecated`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deprecation {
+    pub kind: DeprecatedKind,
+    /// coordinate of the element, e.g. `Image.url`, `Query.image(size:)` or `Format.PNG`
+    pub coordinate: String,
+    pub reason: String,
+    /// span of the name of the element in the document
+    pub span: Span,
+}
+
+impl Deprecation {
+    /// returns the stable code of deprecation warnings
+    pub const fn code(&self) -> &'static str {
+        codes::DEPRECATED.code
+    }
+}
+
+impl fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} `{}` is deprecated: {}",
+            self.kind.as_str(),
+            self.coordinate,
+            self.reason
+        )
+    }
+}
+
+/// Kind of a deprecated schema element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeprecatedKind {
+    Field,
+    Argument,
+    InputField,
+    EnumValue,
+}
+
+impl DeprecatedKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            DeprecatedKind::Field => "field",
+            DeprecatedKind::Argument => "argument",
+            DeprecatedKind::InputField => "input field",
+            DeprecatedKind::EnumValue => "enum value",
+        }
+    }
+}
+
+struct Validator<'s, 'd, 'a> {
+    schema: &'s Schema,
+    fragments: HashMap<&'a str, &'d FragmentDefinition<'a>>,
+    /// variables of the current operation, empty in fragments
+    variables: HashMap<&'a str, &'d VariableDefinition<'a>>,
+    errors: Vec<ValidationError>,
+    deprecations: Vec<Deprecation>,
+}
+
+impl<'s, 'd, 'a> Validator<'s, 'd, 'a> {
+    fn operation(&mut self, operation: &'d OperationDefinition<'a>) {
+        self.variables = operation
+            .variable_definitions
+            .iter()
+            .map(|definition| (definition.variable.value, definition))
+            .collect();
+
+        for definition in &operation.variable_definitions {
+            if self.known_type(definition.ty.named())
+                && let Some(default_value) = &definition.default_value
+            {
+                self.value(default_value, &TypeRef::from(&definition.ty), false);
+            }
+        }
+
+        let Some(root) = self.schema.root(operation.operation) else {
+            let name = operation.operation.as_str();
+
+            self.errors.push(ValidationError::UnknownType {
+                name: format!("{}{}", name[..1].to_ascii_uppercase(), &name[1..]),
+                span: operation.keyword_span(),
+            });
+            return;
+        };
+
+        self.selection_set(&operation.selection_set, root);
+        self.variables.clear();
+    }
+
+    fn fragment(&mut self, fragment: &'d FragmentDefinition<'a>) {
+        let condition = &fragment.type_condition;
+
+        if self.composite_type(condition) {
+            self.selection_set(&fragment.selection_set, condition.value);
+        }
+    }
+
+    /// checks that the type exists, reporting it otherwise
+    fn known_type(&mut self, name: &Name) -> bool {
+        if self.schema.has_type(name.value) {
+            return true;
+        }
+
+        self.errors.push(ValidationError::UnknownType {
+            name: name.value.to_owned(),
+            span: name.span.clone(),
+        });
+
+        false
+    }
+
+    /// checks that the type of a type condition exists and is composite, reporting it otherwise
+    fn composite_type(&mut self, name: &Name) -> bool {
+        if !self.known_type(name) {
+            return false;
+        }
+
+        let is_composite = self
+            .schema
+            .get(name.value)
+            .is_some_and(|ty| ty.kind.is_composite());
+
+        if !is_composite {
+            self.errors.push(ValidationError::InvalidTypeCondition {
+                condition: name.value.to_owned(),
+                parent: None,
+                span: name.span.clone(),
+            });
+        }
+
+        is_composite
+    }
+
+    /// checks that the fragment on `condition` may apply to the `parent` type
+    fn applicable(&mut self, condition: &str, parent: &str, span: &Span) {
+        let possible = self.schema.possible_types(parent);
+
+        if self.schema.possible_types(condition).is_disjoint(&possible) {
+            self.errors.push(ValidationError::InvalidTypeCondition {
+                condition: condition.to_owned(),
+                parent: Some(parent.to_owned()),
+                span: span.clone(),
+            });
+        }
+    }
+
+    fn selection_set(&mut self, selection_set: &'d SelectionSet<'a>, parent: &str) {
+        for selection in &selection_set.selections {
+            match selection {
+                Selection::Field(field) => self.field(field, parent),
+                Selection::FragmentSpread(spread) => {
+                    let condition = self
+                        .fragments
+                        .get(spread.name.value)
+                        .map(|fragment| fragment.type_condition.value);
+
+                    if let Some(condition) = condition
+                        && self
+                            .schema
+                            .get(condition)
+                            .is_some_and(|ty| ty.kind.is_composite())
+                    {
+                        self.applicable(condition, parent, &spread.name.span);
+                    }
+                }
+                Selection::InlineFragment(fragment) => match &fragment.type_condition {
+                    Some(condition) => {
+                        if self.composite_type(condition) {
+                            self.applicable(condition.value, parent, &condition.span);
+                            self.selection_set(&fragment.selection_set, condition.value);
+                        }
+                    }
+                    None => self.selection_set(&fragment.selection_set, parent),
+                },
+            }
+        }
+    }
+
+    fn field(&mut self, field: &'d Field<'a>, parent: &str) {
+        let name = field.name.value;
+
+        if name == "__typename" {
+            return;
+        }
+
+        let Some(definition) = self.schema.get(parent).and_then(|ty| ty.fields.get(name)) else {
+            // introspection fields are not defined in the SDL
+            if !(name.starts_with("__") && self.schema.root(OperationType::Query) == Some(parent)) {
+                self.errors.push(ValidationError::UnknownField {
+                    field: name.to_owned(),
+                    parent: parent.to_owned(),
+                    span: field.name.span.clone(),
+                });
+            }
+
+            return;
+        };
+
+        if let Some(reason) = &definition.deprecated {
+            self.deprecated(
+                DeprecatedKind::Field,
+                format!("{parent}.{name}"),
+                reason,
+                &field.name.span,
+            );
+        }
+
+        self.arguments(&field.arguments, &definition.arguments, parent, name);
+
+        if let Some(selection_set) = &field.selection_set
+            && self
+                .schema
+                .get(definition.ty.named())
+                .is_some_and(|ty| ty.kind.is_composite())
+        {
+            self.selection_set(selection_set, definition.ty.named());
+        }
+    }
+
+    fn arguments(
+        &mut self,
+        arguments: &[Argument<'a>],
+        definitions: &HashMap<String, SchemaField>,
+        parent: &str,
+        field: &str,
+    ) {
+        for argument in arguments {
+            match definitions.get(argument.name.value) {
+                Some(definition) => {
+                    if let Some(reason) = &definition.deprecated {
+                        self.deprecated(
+                            DeprecatedKind::Argument,
+                            format!("{parent}.{field}({}:)", argument.name.value),
+                            reason,
+                            &argument.name.span,
+                        );
+                    }
+
+                    self.value(&argument.value, &definition.ty, definition.has_default);
+                }
+                None => self.errors.push(ValidationError::UnknownArgument {
+                    argument: argument.name.value.to_owned(),
+                    field: format!("{parent}.{field}"),
+                    span: argument.name.span.clone(),
+                }),
+            }
+        }
+    }
+
+    fn deprecated(&mut self, kind: DeprecatedKind, coordinate: String, reason: &str, span: &Span) {
+        self.deprecations.push(Deprecation {
+            kind,
+            coordinate,
+            reason: reason.to_owned(),
+            span: span.clone(),
+        });
+    }
+
+    /// checks variables, input fields and enum values in the value at the position of the `expected` type
+    fn value(&mut self, value: &Value<'a>, expected: &TypeRef, has_default: bool) {
+        match (value, expected) {
+            (Value::Variable(name), _) => self.variable(name, expected, has_default),
+            (_, TypeRef::NonNull(expected)) => self.value(value, expected, false),
+            (Value::List(items, _), TypeRef::List(expected)) => {
+                for item in items {
+                    self.value(item, expected, false);
+                }
+            }
+            // a single value is coerced to a list
+            (_, TypeRef::List(expected)) => self.value(value, expected, false),
+            (Value::Object(fields, _), TypeRef::Named(name)) => {
+                let Some(ty) = self.schema.get(name) else {
+                    return;
+                };
+
+                if ty.kind != SchemaTypeKind::InputObject {
+                    return;
+                }
+
+                for field in fields {
+                    if let Some(definition) = ty.fields.get(field.name.value) {
+                        if let Some(reason) = &definition.deprecated {
+                            self.deprecated(
+                                DeprecatedKind::InputField,
+                                format!("{name}.{}", field.name.value),
+                                reason,
+                                &field.name.span,
+                            );
+                        }
+
+                        self.value(&field.value, &definition.ty, definition.has_default);
+                    }
+                }
+            }
+            (Value::Enum(value), TypeRef::Named(name)) => {
+                if let Some(Some(reason)) = self
+                    .schema
+                    .get(name)
+                    .and_then(|ty| ty.enum_values.get(value.value))
+                {
+                    self.deprecated(
+                        DeprecatedKind::EnumValue,
+                        format!("{name}.{}", value.value),
+                        reason,
+                        &value.span,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn variable(&mut self, name: &Name<'a>, expected: &TypeRef, has_default: bool) {
+        let Some(definition) = self.variables.get(name.value) else {
+            return;
+        };
+
+        let found = TypeRef::from(&definition.ty);
+        let has_default = has_default
+            || definition
+                .default_value
+                .as_ref()
+                .is_some_and(|value| !matches!(value, Value::Null(_)));
+
+        let is_compatible = match (&found, expected) {
+            // a nullable variable with a default value may be used in a non-null position
+            (TypeRef::Named(_) | TypeRef::List(_), TypeRef::NonNull(expected)) if has_default => {
+                is_subtype(&found, expected)
+            }
+            _ => is_subtype(&found, expected),
+        };
+
+        if !is_compatible {
+            self.errors.push(ValidationError::VariableTypeMismatch {
+                variable: name.value.to_owned(),
+                found: found.to_string(),
+                expected: expected.to_string(),
+                span: name.span.clone(),
+            });
+        }
+    }
+}
+
+/// returns whether a value of the `found` type may be used in the position of the `expected` one
+fn is_subtype(found: &TypeRef, expected: &TypeRef) -> bool {
+    match (found, expected) {
+        (TypeRef::NonNull(found), TypeRef::NonNull(expected)) => is_subtype(found, expected),
+        (_, TypeRef::NonNull(_)) => false,
+        (TypeRef::NonNull(found), _) => is_subtype(found, expected),
+        (TypeRef::List(found), TypeRef::List(expected)) => is_subtype(found, expected),
+        (TypeRef::Named(found), TypeRef::Named(expected)) => found == expected,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bumpalo::Bump;
+    use indoc::indoc;
+
+    use super::{DeprecatedKind, Deprecation, ValidationError};
+    use crate::{ParseOptions, Schema, parse};
+
+    const SCHEMA: &str = indoc! {r#"
+        type Query {
+          image(id: ID!, size: Int = 100): Image
+          search(filter: Filter): [SearchResult!]!
+          node(id: ID!): Node
+        }
+
+        interface Node { id: ID! }
+
+        type Image implements Node { id: ID! url(format: Format): String! }
+        type Video implements Node { id: ID! duration: Int }
+        type User { name: String }
+
+        union SearchResult = Image | Video
+
+        enum Format { PNG JPEG }
+        input Filter { ids: [ID!] kind: String }
+
+        extend type Image { alt: String }
+    "#};
+
+    fn validate(source: &str) -> Vec<ValidationError> {
+        let options = ParseOptions::default();
+        let schema = Schema::parse(SCHEMA, &options).unwrap();
+        let arena = Bump::new();
+
+        schema.validate(&parse(source, &arena, &options).unwrap())
+    }
+
+    #[test]
+    fn accepts_valid_documents() {
+        let source = indoc! {r#"
+            query ($id: ID!, $ids: [ID!], $size: Int) {
+              __typename
+              image(id: $id, size: $size) { id url(format: PNG) alt ...img }
+              search(filter: { ids: $ids }) { ... on Image { url } ... on Node { id } }
+              node(id: "1") { ... on Video { duration } ...img }
+            }
+
+            fragment img on Image { id }
+        "#};
+
+        assert_eq!(validate(source), []);
+    }
+
+    #[test]
+    fn reports_unknown_fields_and_types() {
+        let source =
+            "query ($f: Filtr) { image(id: 1) { size } video { id } } fragment F on Picture { id }";
+
+        assert_eq!(
+            validate(source),
+            [
+                ValidationError::UnknownType {
+                    name: "Filtr".into(),
+                    span: 11..16
+                },
+                ValidationError::UnknownField {
+                    field: "size".into(),
+                    parent: "Image".into(),
+                    span: 35..39
+                },
+                ValidationError::UnknownField {
+                    field: "video".into(),
+                    parent: "Query".into(),
+                    span: 42..47
+                },
+                ValidationError::UnknownType {
+                    name: "Picture".into(),
+                    span: 71..78
+                },
+            ]
+        );
+        assert_eq!(
+            validate("mutation { a }"),
+            [ValidationError::UnknownType {
+                name: "Mutation".into(),
+                span: 0..8
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_unknown_arguments() {
+        assert_eq!(
+            validate("{ image(id: 1, width: 2) { url(size: 3) } }"),
+            [
+                ValidationError::UnknownArgument {
+                    argument: "width".into(),
+                    field: "Query.image".into(),
+                    span: 15..20
+                },
+                ValidationError::UnknownArgument {
+                    argument: "size".into(),
+                    field: "Image.url".into(),
+                    span: 31..35
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_type_conditions() {
+        let source = "{ image(id: 1) { ... on Video { id } ...user ... on Format { a } } } fragment user on User { name }";
+
+        assert_eq!(
+            validate(source),
+            [
+                ValidationError::InvalidTypeCondition {
+                    condition: "Video".into(),
+                    parent: Some("Image".into()),
+                    span: 24..29
+                },
+                ValidationError::InvalidTypeCondition {
+                    condition: "User".into(),
+                    parent: Some("Image".into()),
+                    span: 40..44
+                },
+                ValidationError::InvalidTypeCondition {
+                    condition: "Format".into(),
+                    parent: None,
+                    span: 52..58
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_variable_type_mismatches() {
+        let source = indoc! {r#"
+            query ($id: ID, $size: String, $ids: [String], $default: ID = "1") {
+              image(id: $id, size: $size) { id }
+              search(filter: { ids: $ids }) { __typename }
+              node(id: $default) { id }
+            }
+        "#};
+
+        assert_eq!(
+            validate(source),
+            [
+                ValidationError::VariableTypeMismatch {
+                    variable: "id".into(),
+                    found: "ID".into(),
+                    expected: "ID!".into(),
+                    span: 81..84
+                },
+                ValidationError::VariableTypeMismatch {
+                    variable: "size".into(),
+                    found: "String".into(),
+                    expected: "Int".into(),
+                    span: 92..97
+                },
+                ValidationError::VariableTypeMismatch {
+                    variable: "ids".into(),
+                    found: "[String]".into(),
+                    expected: "[ID!]".into(),
+                    span: 130..134
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_deprecations() {
+        let schema = indoc! {r#"
+            type Query {
+              image(id: ID!, size: Int @deprecated(reason: "Use `width`"), width: Int): Image
+              images(filter: Filter, format: Format = PNG): [Image!]!
+            }
+
+            type Image {
+              url: String! @deprecated(reason: """
+                Use `src` instead
+              """)
+              src: String!
+            }
+
+            input Filter { tag: String @deprecated, tags: [String!] }
+            enum Format { PNG GIF @deprecated(reason: "No animations") }
+        "#};
+        let source = indoc! {r#"
+            query ($format: Format = GIF) {
+              image(id: 1, size: 100) { url src }
+              images(filter: { tag: "a", tags: ["b"] }, format: $format) { src }
+              gifs: images(format: GIF) { src }
+            }
+        "#};
+
+        let options = ParseOptions::default();
+        let schema = Schema::parse(schema, &options).unwrap();
+        let arena = Bump::new();
+        let deprecations = schema.deprecations(&parse(source, &arena, &options).unwrap());
+
+        assert_eq!(
+            deprecations,
+            [
+                Deprecation {
+                    kind: DeprecatedKind::EnumValue,
+                    coordinate: "Format.GIF".into(),
+                    reason: "No animations".into(),
+                    span: 25..28
+                },
+                Deprecation {
+                    kind: DeprecatedKind::Argument,
+                    coordinate: "Query.image(size:)".into(),
+                    reason: "Use `width`".into(),
+                    span: 47..51
+                },
+                Deprecation {
+                    kind: DeprecatedKind::Field,
+                    coordinate: "Image.url".into(),
+                    reason: "Use `src` instead".into(),
+                    span: 60..63
+                },
+                Deprecation {
+                    kind: DeprecatedKind::InputField,
+                    coordinate: "Filter.tag".into(),
+                    reason: "No longer supported".into(),
+                    span: 89..92
+                },
+                Deprecation {
+                    kind: DeprecatedKind::EnumValue,
+                    coordinate: "Format.GIF".into(),
+                    reason: "No animations".into(),
+                    span: 162..165
+                },
+            ]
+        );
+    }
+}
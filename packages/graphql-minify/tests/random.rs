@@ -1,4 +1,4 @@
-use graphql_minify::{MinifyAllocator, minify};
+use graphql_minify::{Bump, Dialect, MinifyAllocator, ParseOptions, format, minify, parse};
 use graphql_semantic_compare::{GraphqlSemanticEquality, cmp_documents};
 use test_each_file::test_each_file;
 
@@ -15,4 +15,19 @@ fn test(file: &str) {
         GraphqlSemanticEquality::Equal,
         "documents are not equal"
     );
+
+    let options = ParseOptions {
+        dialect: Dialect::EXPERIMENTAL,
+        ..ParseOptions::default()
+    };
+    parse(file, &Bump::new(), &options).expect("parsing failed");
+    parse(&minified, &Bump::new(), &options).expect("parsing of minified document failed");
+
+    let formatted = format(file, &mut alloc).expect("formatting failed");
+
+    assert_eq!(
+        cmp_documents(file, &formatted),
+        GraphqlSemanticEquality::Equal,
+        "formatted documents are not equal"
+    );
 }
@@ -0,0 +1,454 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use graphql_minify::{Dialect, LintRule, TransformOptions};
+use serde::Deserialize;
+
+/// Plugin configuration passed from the SWC config
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Config {
+    /// processing mode, either the same for all environments or a per-environment one
+    #[serde(default)]
+    pub mode: ModeConfig,
+    /// whether to minify literals with invalid tokens, passing the tokens through verbatim
+    #[serde(default)]
+    pub recover: bool,
+    /// whether to report whitespace characters that GraphQL does not ignore (e.g. NBSP) instead of stripping them
+    #[serde(default)]
+    pub strict_whitespace: bool,
+    /// whether to remove descriptions when minifying, e.g. from schemas shipped to clients
+    #[serde(default)]
+    pub strip_descriptions: bool,
+    /// names of directives without `@` to remove with their arguments when minifying
+    #[serde(default)]
+    pub remove_directives: Vec<String>,
+    /// transforms applied to literals before processing
+    #[serde(default)]
+    pub transform: TransformConfig,
+    /// severity of the diagnostic for template expressions that break GraphQL tokens
+    #[serde(default)]
+    pub expr_breaks_token: Severity,
+    /// severity of the diagnostic for literals that cannot be parsed as GraphQL,
+    /// such literals are left unchanged if it is an error
+    #[serde(default)]
+    pub validate_syntax: Severity,
+    /// schema to validate operations and fragments against
+    #[serde(default)]
+    pub schema: Option<SchemaConfig>,
+    /// severities of lint rules of operations and fragments
+    #[serde(default)]
+    pub lint: LintConfig,
+    /// experimental GraphQL syntax accepted in addition to the spec
+    #[serde(default)]
+    pub dialect: DialectConfig,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ModeConfig {
+    /// same mode for all environments
+    Single(Mode),
+    /// mode per environment name, environments not listed fall back to [`Mode::Minify`]
+    PerEnv(HashMap<String, Mode>),
+}
+
+impl Default for ModeConfig {
+    fn default() -> Self {
+        Self::Single(Mode::default())
+    }
+}
+
+impl ModeConfig {
+    /// returns the mode for the environment the plugin is running in
+    pub fn resolve(&self, env: Option<&str>) -> Mode {
+        match self {
+            Self::Single(mode) => *mode,
+            Self::PerEnv(modes) => env
+                .and_then(|env| modes.get(env))
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// How GraphQL literals are processed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Mode {
+    /// minify literals
+    #[default]
+    Minify,
+    /// pretty-print literals with indentation
+    Format,
+    /// leave literals unchanged
+    Off,
+}
+
+/// Source of the schema SDL
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub enum SchemaConfig {
+    /// path to the SDL file as seen by the plugin, e.g. `/cwd/schema.graphql`
+    Path(String),
+    /// SDL itself
+    Sdl(String),
+}
+
+impl SchemaConfig {
+    /// returns the SDL, reading it from the file if necessary
+    pub fn read(&self) -> std::io::Result<Cow<'_, str>> {
+        match self {
+            Self::Path(path) => std::fs::read_to_string(path).map(Cow::Owned),
+            Self::Sdl(sdl) => Ok(Cow::Borrowed(sdl)),
+        }
+    }
+
+    /// returns the modification time of the SDL file, if it is known
+    pub fn modified(&self) -> Option<SystemTime> {
+        match self {
+            Self::Path(path) => std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok(),
+            Self::Sdl(_) => None,
+        }
+    }
+}
+
+/// Severities of [`LintRule`]s, all rules are off by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LintConfig {
+    #[serde(default = "Severity::off")]
+    pub anonymous_operation: Severity,
+    #[serde(default = "Severity::off")]
+    pub duplicate_operation_name: Severity,
+    #[serde(default = "Severity::off")]
+    pub unused_variable: Severity,
+    #[serde(default = "Severity::off")]
+    pub undefined_variable: Severity,
+    #[serde(default = "Severity::off")]
+    pub unknown_fragment: Severity,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            anonymous_operation: Severity::Off,
+            duplicate_operation_name: Severity::Off,
+            unused_variable: Severity::Off,
+            undefined_variable: Severity::Off,
+            unknown_fragment: Severity::Off,
+        }
+    }
+}
+
+impl LintConfig {
+    /// returns the severity of the rule
+    pub const fn severity(self, rule: LintRule) -> Severity {
+        match rule {
+            LintRule::AnonymousOperation => self.anonymous_operation,
+            LintRule::DuplicateOperationName => self.duplicate_operation_name,
+            LintRule::UnusedVariable => self.unused_variable,
+            LintRule::UndefinedVariable => self.undefined_variable,
+            LintRule::UnknownFragment => self.unknown_fragment,
+        }
+    }
+}
+
+/// Transforms of [`TransformOptions`] enabled in the config
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TransformConfig {
+    #[serde(default)]
+    pub remove_client_fields: bool,
+    #[serde(default)]
+    pub add_typename: bool,
+    /// naming of anonymous operations after the bindings of literals
+    #[serde(default)]
+    pub name_operations: Option<NameOperationsConfig>,
+}
+
+/// Operation names are given per literal, so they are left out
+impl From<&TransformConfig> for TransformOptions {
+    fn from(config: &TransformConfig) -> Self {
+        Self {
+            remove_client_fields: config.remove_client_fields,
+            add_typename: config.add_typename,
+            operation_name: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NameOperationsConfig {
+    /// pattern of names with the binding name substituted for `{name}` as written
+    /// and for `{Name}` in `PascalCase`
+    pub pattern: String,
+    /// severity of the diagnostic for given names used by other operations of the module
+    #[serde(default)]
+    pub collision: Severity,
+}
+
+impl NameOperationsConfig {
+    /// returns the name of the operation assigned to the `binding`,
+    /// or [`None`] if it is not a valid GraphQL name, e.g. with `$`
+    pub fn name(&self, binding: &str) -> Option<String> {
+        let name = self
+            .pattern
+            .replace("{name}", binding)
+            .replace("{Name}", &pascal_case(binding));
+
+        let mut chars = name.chars();
+        let is_valid = chars
+            .next()
+            .is_some_and(|char| char == '_' || char.is_ascii_alphabetic())
+            && chars.all(|char| char == '_' || char.is_ascii_alphanumeric());
+
+        is_valid.then_some(name)
+    }
+}
+
+/// converts `camelCase` and `SCREAMING_SNAKE_CASE` names to `PascalCase`
+fn pascal_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+
+    for word in name.split(['_', '$']) {
+        let is_screaming = !word.chars().any(|char| char.is_ascii_lowercase());
+
+        for (i, char) in word.chars().enumerate() {
+            result.push(if i == 0 {
+                char.to_ascii_uppercase()
+            } else if is_screaming {
+                char.to_ascii_lowercase()
+            } else {
+                char
+            });
+        }
+    }
+
+    result
+}
+
+/// Extensions of [`Dialect`] enabled in the config
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DialectConfig {
+    #[serde(default)]
+    pub client_controlled_nullability: bool,
+    #[serde(default)]
+    pub fragment_arguments: bool,
+    #[serde(default)]
+    pub operation_descriptions: bool,
+}
+
+impl From<DialectConfig> for Dialect {
+    fn from(config: DialectConfig) -> Self {
+        Self {
+            client_controlled_nullability: config.client_controlled_nullability,
+            fragment_arguments: config.fragment_arguments,
+            operation_descriptions: config.operation_descriptions,
+        }
+    }
+}
+
+/// Severity of an optional diagnostic
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    /// do not report
+    Off,
+    /// report as a warning
+    #[default]
+    Warn,
+    /// report as an error
+    Error,
+}
+
+impl Severity {
+    const fn off() -> Self {
+        Self::Off
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graphql_minify::{Dialect, LintRule, TransformOptions};
+
+    use super::{Config, Mode, NameOperationsConfig, SchemaConfig, Severity};
+
+    fn parse(json: &str) -> Config {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn defaults_to_minify() {
+        assert_eq!(parse("{}").mode.resolve(None), Mode::Minify);
+        assert_eq!(parse("{}").mode.resolve(Some("development")), Mode::Minify);
+    }
+
+    #[test]
+    fn resolves_single_mode() {
+        let config = parse(r#"{ "mode": "format" }"#);
+
+        assert_eq!(config.mode.resolve(None), Mode::Format);
+        assert_eq!(config.mode.resolve(Some("production")), Mode::Format);
+    }
+
+    #[test]
+    fn resolves_per_env_mode() {
+        let config = parse(r#"{ "mode": { "development": "format", "test": "off" } }"#);
+
+        assert_eq!(config.mode.resolve(Some("development")), Mode::Format);
+        assert_eq!(config.mode.resolve(Some("test")), Mode::Off);
+        assert_eq!(config.mode.resolve(Some("production")), Mode::Minify);
+        assert_eq!(config.mode.resolve(None), Mode::Minify);
+    }
+
+    #[test]
+    fn parses_recover() {
+        assert!(!parse("{}").recover);
+        assert!(parse(r#"{ "recover": true }"#).recover);
+    }
+
+    #[test]
+    fn parses_strict_whitespace() {
+        assert!(!parse("{}").strict_whitespace);
+        assert!(parse(r#"{ "strictWhitespace": true }"#).strict_whitespace);
+    }
+
+    #[test]
+    fn parses_strip_descriptions() {
+        assert!(!parse("{}").strip_descriptions);
+        assert!(parse(r#"{ "stripDescriptions": true }"#).strip_descriptions);
+    }
+
+    #[test]
+    fn parses_remove_directives() {
+        assert!(parse("{}").remove_directives.is_empty());
+        assert_eq!(
+            parse(r#"{ "removeDirectives": ["client", "connection"] }"#).remove_directives,
+            ["client", "connection"]
+        );
+    }
+
+    #[test]
+    fn parses_transform() {
+        assert!(!TransformOptions::from(&parse("{}").transform).is_enabled());
+        assert_eq!(
+            TransformOptions::from(
+                &parse(r#"{ "transform": { "removeClientFields": true, "addTypename": true } }"#)
+                    .transform
+            ),
+            TransformOptions {
+                remove_client_fields: true,
+                add_typename: true,
+                operation_name: None
+            }
+        );
+        assert!(
+            serde_json::from_str::<Config>(r#"{ "transform": { "removeClient": true } }"#).is_err()
+        );
+    }
+
+    #[test]
+    fn names_operations() {
+        let config = parse(r#"{ "transform": { "nameOperations": { "pattern": "{Name}" } } }"#)
+            .transform
+            .name_operations
+            .unwrap();
+
+        assert_eq!(config.collision, Severity::Warn);
+        assert_eq!(config.name("GetUser").as_deref(), Some("GetUser"));
+        assert_eq!(config.name("getUser").as_deref(), Some("GetUser"));
+        assert_eq!(config.name("GET_USER_2").as_deref(), Some("GetUser2"));
+        assert_eq!(config.name("$user").as_deref(), Some("User"));
+
+        let config = NameOperationsConfig {
+            pattern: "App_{name}".to_owned(),
+            collision: Severity::Error,
+        };
+
+        assert_eq!(config.name("GET_USER").as_deref(), Some("App_GET_USER"));
+        assert_eq!(config.name("$user"), None);
+    }
+
+    #[test]
+    fn parses_severity() {
+        assert_eq!(parse("{}").expr_breaks_token, Severity::Warn);
+        assert_eq!(
+            parse(r#"{ "exprBreaksToken": "off" }"#).expr_breaks_token,
+            Severity::Off
+        );
+        assert_eq!(
+            parse(r#"{ "exprBreaksToken": "error" }"#).expr_breaks_token,
+            Severity::Error
+        );
+    }
+
+    #[test]
+    fn parses_validate_syntax() {
+        assert_eq!(parse("{}").validate_syntax, Severity::Warn);
+        assert_eq!(Config::default().validate_syntax, Severity::Warn);
+        assert_eq!(
+            parse(r#"{ "validateSyntax": "error" }"#).validate_syntax,
+            Severity::Error
+        );
+    }
+
+    #[test]
+    fn parses_schema() {
+        assert_eq!(parse("{}").schema, None);
+        assert_eq!(
+            parse(r#"{ "schema": { "path": "/cwd/schema.graphql" } }"#).schema,
+            Some(SchemaConfig::Path("/cwd/schema.graphql".into()))
+        );
+        assert_eq!(
+            parse(r#"{ "schema": { "sdl": "type Query { a: Int }" } }"#).schema,
+            Some(SchemaConfig::Sdl("type Query { a: Int }".into()))
+        );
+        assert!(serde_json::from_str::<Config>(r#"{ "schema": "schema.graphql" }"#).is_err());
+    }
+
+    #[test]
+    fn parses_lint() {
+        let lint = parse("{}").lint;
+
+        assert_eq!(lint.severity(LintRule::AnonymousOperation), Severity::Off);
+        assert_eq!(lint.severity(LintRule::UnusedVariable), Severity::Off);
+
+        let lint =
+            parse(r#"{ "lint": { "anonymousOperation": "warn", "unknownFragment": "error" } }"#)
+                .lint;
+
+        assert_eq!(lint.severity(LintRule::AnonymousOperation), Severity::Warn);
+        assert_eq!(lint.severity(LintRule::UnknownFragment), Severity::Error);
+        assert_eq!(lint.severity(LintRule::UndefinedVariable), Severity::Off);
+        assert!(
+            serde_json::from_str::<Config>(r#"{ "lint": { "unusedVariables": "off" } }"#).is_err()
+        );
+    }
+
+    #[test]
+    fn parses_dialect() {
+        assert_eq!(Dialect::from(parse("{}").dialect), Dialect::SPEC);
+        assert_eq!(
+            Dialect::from(parse(r#"{ "dialect": { "fragmentArguments": true } }"#).dialect),
+            Dialect {
+                fragment_arguments: true,
+                ..Dialect::SPEC
+            }
+        );
+        assert!(
+            serde_json::from_str::<Config>(r#"{ "dialect": { "fragmentArgs": true } }"#).is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_options() {
+        assert!(serde_json::from_str::<Config>(r#"{ "mod": "format" }"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{ "mode": "pretty" }"#).is_err());
+    }
+}
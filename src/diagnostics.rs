@@ -0,0 +1,423 @@
+//! Reporting of GraphQL errors
+//!
+//! Errors are labeled with the position inside the GraphQL document rather than in the file,
+//! since the former is what the user sees when reading the literal
+
+use std::fmt;
+use std::ops::Range;
+
+use graphql_minify::MinifyError;
+use swc_core::common::Span;
+use swc_core::common::errors::{DiagnosticId, HANDLER};
+
+use crate::config::Severity;
+use crate::joined_tpl::JoinedTpl;
+use crate::str_span::StrSpan;
+
+/// Stable codes of plugin diagnostics, described in the [`graphql_minify::codes`] catalogue
+///
+/// Codes of errors occurred in GraphQL documents are provided by [`graphql_minify::codes`] as well
+pub(crate) mod codes {
+    use graphql_minify::codes as catalogue;
+
+    pub const MINIFY_FAILED: &str = catalogue::MINIFY_FAILED.code;
+    pub const FORMAT_FAILED: &str = catalogue::FORMAT_FAILED.code;
+    pub const RECOVERED: &str = catalogue::RECOVERED.code;
+    pub const KIND_MISMATCH: &str = catalogue::KIND_MISMATCH.code;
+    pub const UNKNOWN_MARKER_OPTIONS: &str = catalogue::UNKNOWN_MARKER_OPTIONS.code;
+    pub const EXPR_BREAKS_TOKEN: &str = catalogue::EXPR_BREAKS_TOKEN.code;
+    pub const INVALID_SYNTAX: &str = catalogue::INVALID_SYNTAX.code;
+    pub const SCHEMA_MISMATCH: &str = catalogue::SCHEMA_MISMATCH.code;
+    pub const INVALID_SCHEMA: &str = catalogue::INVALID_SCHEMA.code;
+    pub const DEPRECATED_USAGE: &str = catalogue::DEPRECATED_USAGE.code;
+    pub const LINT: &str = catalogue::LINT.code;
+    pub const EMPTIED_SELECTION: &str = catalogue::EMPTIED_SELECTION.code;
+    pub const NAME_COLLISION: &str = catalogue::NAME_COLLISION.code;
+}
+
+/// returns [`DiagnosticId`] of the stable code, warnings are identified as lints
+pub(crate) fn diagnostic_id(code: &str, severity: Severity) -> DiagnosticId {
+    match severity {
+        Severity::Warn => DiagnosticId::Lint(code.to_owned()),
+        Severity::Off | Severity::Error => DiagnosticId::Error(code.to_owned()),
+    }
+}
+
+/// maximum number of characters of the offending token shown in the label
+const MAX_TOKEN_CHARS: usize = 16;
+
+/// Location of a string value inside the GraphQL document of a literal
+///
+/// For [`Tpl`], the document consists of all its [`TplElement`]s with expressions omitted
+///
+/// [`Tpl`]: swc_core::ecma::ast::Tpl
+/// [`TplElement`]: swc_core::ecma::ast::TplElement
+pub(crate) struct ValueLocation<'a> {
+    document: &'a str,
+    /// range of the value in `document`
+    value: Range<usize>,
+    /// whether the value is preceded by an expression
+    after_expr: bool,
+    /// whether the value is followed by an expression
+    before_expr: bool,
+}
+
+impl<'a> ValueLocation<'a> {
+    /// location of the value that is the whole document
+    pub fn whole(document: &'a str) -> Self {
+        Self {
+            document,
+            value: 0..document.len(),
+            after_expr: false,
+            before_expr: false,
+        }
+    }
+
+    /// location of the `index`-th [`TplElement`] of the template joined without expressions
+    ///
+    /// [`TplElement`]: swc_core::ecma::ast::TplElement
+    pub fn quasi(joined: &'a JoinedTpl, index: usize) -> Self {
+        Self {
+            document: &joined.code,
+            value: joined.quasi_range(index),
+            after_expr: index != 0,
+            before_expr: index + 1 != joined.quasi_count(),
+        }
+    }
+
+    /// returns 1-based line and column (in characters) of the offset in the value
+    fn line_col(&self, offset: usize) -> (usize, usize) {
+        graphql_minify::line_column(self.document, self.value.start + offset)
+    }
+
+    fn value(&self) -> &str {
+        &self.document[self.value.clone()]
+    }
+}
+
+/// Diagnostic collecting [`MinifyError`]s of a literal, so that all of them are reported at once
+pub(crate) struct ErrorReport<'a> {
+    code: &'static str,
+    message: &'a str,
+    /// [`Severity::Warn`] if the errors are reported as a warning, e.g. when they were recovered from
+    severity: Severity,
+    labels: Vec<(Span, String)>,
+    helps: Vec<&'static str>,
+}
+
+impl<'a> ErrorReport<'a> {
+    pub fn new(code: &'static str, message: &'a str) -> Self {
+        Self {
+            code,
+            message,
+            severity: Severity::Error,
+            labels: Vec::new(),
+            helps: Vec::new(),
+        }
+    }
+
+    pub fn warning(code: &'static str, message: &'a str) -> Self {
+        Self {
+            severity: Severity::Warn,
+            ..Self::new(code, message)
+        }
+    }
+
+    /// adds [`MinifyError`] occurred at `err_value_span` of the `str` value
+    pub fn add<Str>(
+        &mut self,
+        str: &Str,
+        location: &ValueLocation,
+        err: &MinifyError,
+        err_value_span: Range<usize>,
+    ) where
+        Str: StrSpan,
+    {
+        let (line, column) = location.line_col(err_value_span.start);
+        let token = match err {
+            // invisible characters are shown by their code points
+            MinifyError::UnexpectedCharacter(c, _) if c.is_whitespace() || *c == '\u{feff}' => {
+                c.escape_unicode().to_string()
+            }
+            _ => truncate_token(&location.value()[err_value_span.clone()]),
+        };
+
+        let label = format!("{} at {line}:{column}", describe(err, &token));
+
+        self.labels
+            .push((str.cooked_span(err_value_span.clone()), label));
+
+        if let Some(help) = help(err, location, err_value_span)
+            && !self.helps.contains(&help)
+        {
+            self.helps.push(help);
+        }
+    }
+
+    /// adds [`MinifyError`] occurred in the `token` of the value of the expression at `expr_span`
+    pub fn add_in_expr(&mut self, expr_span: Span, err: &MinifyError, token: &str) {
+        let label = format!(
+            "{} in the value of the expression",
+            describe(err, &truncate_token(token))
+        );
+
+        self.labels.push((expr_span, label));
+    }
+
+    /// adds an error with the stable `code` occurred at `err_value_span` of the `str` value,
+    /// e.g. a [`ValidationError`](graphql_minify::ValidationError)
+    pub fn add_message<Str>(
+        &mut self,
+        str: &Str,
+        location: &ValueLocation,
+        code: &str,
+        message: impl fmt::Display,
+        err_value_span: Range<usize>,
+    ) where
+        Str: StrSpan,
+    {
+        let (line, column) = location.line_col(err_value_span.start);
+        let label = format!("[{code}] {message} at {line}:{column}");
+
+        self.labels.push((str.cooked_span(err_value_span), label));
+    }
+
+    /// adds an error with the stable `code` occurred in the value of the expression at `expr_span`
+    pub fn add_message_in_expr(&mut self, expr_span: Span, code: &str, message: impl fmt::Display) {
+        let label = format!("[{code}] {message} in the value of the expression");
+
+        self.labels.push((expr_span, label));
+    }
+
+    /// emits the diagnostic for the literal at `outer_span`, if any error was added
+    pub fn emit(self, outer_span: Span) {
+        if self.labels.is_empty() {
+            return;
+        }
+
+        HANDLER.with(|handler| {
+            let mut diagnostic = if self.severity == Severity::Warn {
+                handler.struct_span_warn(outer_span, self.message)
+            } else {
+                handler.struct_span_err(outer_span, self.message)
+            };
+
+            diagnostic.code(diagnostic_id(self.code, self.severity));
+
+            for (span, label) in self.labels {
+                diagnostic.span_label(span, label);
+            }
+
+            for help in self.helps {
+                diagnostic.help(help);
+            }
+
+            diagnostic.emit();
+        });
+    }
+}
+
+/// reports the expression at `expr_span` that splits a GraphQL name or number into the `before` and `after` parts
+pub(crate) fn report_broken_token(expr_span: Span, severity: Severity, before: &str, after: &str) {
+    const MESSAGE: &str = "template expression breaks a GraphQL token";
+
+    let label = format!("expression is glued to `{before}` and `{after}`");
+
+    HANDLER.with(|handler| {
+        let mut diagnostic = match severity {
+            Severity::Off => return,
+            Severity::Warn => handler.struct_span_warn(expr_span, MESSAGE),
+            Severity::Error => handler.struct_span_err(expr_span, MESSAGE),
+        };
+
+        diagnostic
+            .code(diagnostic_id(codes::EXPR_BREAKS_TOKEN, severity))
+            .span_label(expr_span, label)
+            .note(
+                "templates are minified in parts, so the value of the expression may be separated \
+                 from the adjacent characters and become a separate token at runtime",
+            )
+            .help("interpolate whole tokens only or pass the values as GraphQL variables")
+            .emit();
+    });
+}
+
+/// reports that transforms remove all selections of the literal at `outer_span`,
+/// which is the contents of a selection set and is left unchanged
+pub(crate) fn report_emptied_selection(outer_span: Span) {
+    HANDLER.with(|handler| {
+        handler
+            .struct_span_err(
+                outer_span,
+                "all GraphQL selections are removed by transforms",
+            )
+            .code(diagnostic_id(codes::EMPTIED_SELECTION, Severity::Error))
+            .span_label(
+                outer_span,
+                "interpolating nothing would leave an empty selection set",
+            )
+            .help("remove the interpolation of the literal or keep some of its selections")
+            .emit();
+    });
+}
+
+/// reports the `name` given to the anonymous operation of the literal at `outer_span`,
+/// which is also used by the operation of the literal at `other_span`
+pub(crate) fn report_name_collision(
+    outer_span: Span,
+    other_span: Span,
+    name: &str,
+    severity: Severity,
+) {
+    const MESSAGE: &str = "given GraphQL operation name is used by another operation";
+
+    HANDLER.with(|handler| {
+        let mut diagnostic = match severity {
+            Severity::Off => return,
+            Severity::Warn => handler.struct_span_warn(outer_span, MESSAGE),
+            Severity::Error => handler.struct_span_err(outer_span, MESSAGE),
+        };
+
+        diagnostic
+            .code(diagnostic_id(codes::NAME_COLLISION, severity))
+            .span_label(outer_span, format!("operation is named `{name}`"))
+            .span_label(other_span, format!("`{name}` is also used here"))
+            .help("name the operation explicitly or rename the binding")
+            .emit();
+    });
+}
+
+/// reports that the configured schema cannot be loaded, so literals are not validated against it
+pub(crate) fn report_invalid_schema(message: &str) {
+    HANDLER.with(|handler| {
+        handler
+            .struct_err(message)
+            .code(diagnostic_id(codes::INVALID_SCHEMA, Severity::Error))
+            .help("check the `schema` option of the plugin config")
+            .emit();
+    });
+}
+
+/// returns the code and the message of the error at the `token`
+fn describe(err: &MinifyError, token: &str) -> String {
+    match err {
+        MinifyError::UnsupportedSyntax(extension, _) => {
+            format!(
+                "[{}] {} of {} `{token}`",
+                err.code(),
+                err.as_str(),
+                extension.name()
+            )
+        }
+        MinifyError::SyntaxError(expected, _) if token.is_empty() => format!(
+            "[{}] {}, expected {expected}, found the end of the document",
+            err.code(),
+            err.as_str()
+        ),
+        MinifyError::SyntaxError(expected, _) => format!(
+            "[{}] {}, expected {expected}, found `{token}`",
+            err.code(),
+            err.as_str()
+        ),
+        _ if token.is_empty() => format!("[{}] {}", err.code(), err.as_str()),
+        _ => format!("[{}] {} `{token}`", err.code(), err.as_str()),
+    }
+}
+
+/// suggests a fix for common mistakes
+fn help(err: &MinifyError, location: &ValueLocation, span: Range<usize>) -> Option<&'static str> {
+    let value = location.value();
+
+    match err {
+        MinifyError::UnterminatedString(_) => Some(
+            "GraphQL strings cannot contain line breaks, use a block string (`\"\"\"`) instead",
+        ),
+        // a string that is interrupted by an expression is seen as an unterminated one
+        MinifyError::UnknownToken(_)
+            if value[span.clone()].starts_with('"')
+                && ((location.before_expr && !value[span.end..].contains('"'))
+                    || (location.after_expr && !value[..span.start].contains('"'))) =>
+        {
+            Some(
+                "expressions cannot be used inside GraphQL strings, pass the value as a variable instead",
+            )
+        }
+        // a block string that is interrupted by an expression is seen as an unterminated one
+        MinifyError::UnterminatedBlockString(_) if location.before_expr => Some(
+            "expressions cannot be used inside GraphQL block strings, pass the value as a variable instead",
+        ),
+        MinifyError::InvalidEscapeSequence(_) => Some(
+            "GraphQL strings allow only `\\\"`, `\\\\`, `\\/`, `\\b`, `\\f`, `\\n`, `\\r`, `\\t`, \
+             `\\uXXXX` and `\\u{X…}` escape sequences",
+        ),
+        MinifyError::UnsupportedSyntax(..) => Some(
+            "experimental syntax can be enabled with the `dialect` option of the plugin config",
+        ),
+        MinifyError::UnexpectedCharacter('\'', _) => {
+            Some("GraphQL strings must be enclosed in double quotes")
+        }
+        MinifyError::UnexpectedCharacter(c, _) if c.is_whitespace() || *c == '\u{feff}' => Some(
+            "GraphQL ignores only spaces, tabs, line breaks and commas, replace the character with a space",
+        ),
+        _ => None,
+    }
+}
+
+/// returns the first line of the token truncated to [`MAX_TOKEN_CHARS`] characters
+fn truncate_token(token: &str) -> String {
+    let line = token.lines().next().unwrap_or_default();
+    let mut truncated = line.chars().take(MAX_TOKEN_CHARS).collect::<String>();
+
+    if truncated.len() < token.trim_end().len() {
+        truncated.push('…');
+    }
+
+    truncated
+}
+
+#[cfg(test)]
+mod test {
+    use swc_core::common::errors::DiagnosticId;
+
+    use super::{ValueLocation, codes, diagnostic_id, truncate_token};
+    use crate::config::Severity;
+
+    #[test]
+    fn computes_line_and_column() {
+        let location = ValueLocation::whole("query {\n  ïd\n  url");
+
+        assert_eq!(location.line_col(0), (1, 1));
+        assert_eq!(location.line_col(6), (1, 7));
+        assert_eq!(location.line_col(8), (2, 1));
+        assert_eq!(location.line_col(12), (2, 4));
+        assert_eq!(location.line_col(16), (3, 3));
+
+        let location = ValueLocation::whole("{\r\n  a\r  b }");
+
+        assert_eq!(location.line_col(5), (2, 3));
+        assert_eq!(location.line_col(9), (3, 3));
+    }
+
+    #[test]
+    fn identifies_warnings_as_lints() {
+        assert_eq!(
+            diagnostic_id(codes::LINT, Severity::Warn),
+            DiagnosticId::Lint("GQLMIN111".into())
+        );
+        assert_eq!(
+            diagnostic_id(codes::LINT, Severity::Error),
+            DiagnosticId::Error("GQLMIN111".into())
+        );
+    }
+
+    #[test]
+    fn truncates_long_tokens() {
+        assert_eq!(truncate_token("%"), "%");
+        assert_eq!(truncate_token("\")\n"), "\")");
+        assert_eq!(truncate_token("\"abc\n  \""), "\"abc…");
+        assert_eq!(
+            truncate_token("\"a very long string literal\""),
+            "\"a very long str…"
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use std::ops::Range;
+
+use swc_core::common::Span;
+use swc_core::ecma::ast::{Tpl, TplElement};
+
+use crate::visitor::{set_tpl_el_value, tpl_el_value};
+
+/// Values of all [`TplElement`]s of a template joined into a single document
+pub struct JoinedTpl {
+    pub code: String,
+    /// ranges of [`TplElement`]s in `code`
+    quasi_ranges: Vec<Range<usize>>,
+}
+
+impl JoinedTpl {
+    /// joins values of `quasis`, replacing the `i`-th expression with `expr(i)`
+    pub fn new<E>(quasis: &[TplElement], mut expr: impl FnMut(usize) -> E) -> Self
+    where
+        E: AsRef<str>,
+    {
+        let mut code = String::new();
+        let mut quasi_ranges = Vec::with_capacity(quasis.len());
+
+        for (i, tpl_el) in quasis.iter().enumerate() {
+            if i != 0 {
+                code.push_str(expr(i - 1).as_ref());
+            }
+
+            let start = code.len();
+            code.push_str(tpl_el_value(tpl_el));
+            quasi_ranges.push(start..code.len());
+        }
+
+        Self { code, quasi_ranges }
+    }
+
+    /// returns the range of the `index`-th [`TplElement`] in `code`
+    pub fn quasi_range(&self, index: usize) -> Range<usize> {
+        self.quasi_ranges[index].clone()
+    }
+
+    pub fn quasi_count(&self) -> usize {
+        self.quasi_ranges.len()
+    }
+
+    /// returns the index of the [`TplElement`] containing the start of `range`
+    /// and the range relative to its value, truncated to the end of the value
+    pub fn locate(&self, range: Range<usize>) -> (usize, Range<usize>) {
+        let index = self
+            .quasi_ranges
+            .partition_point(|quasi| quasi.start <= range.start)
+            .saturating_sub(1);
+
+        let Range { start, end } = self.quasi_ranges[index];
+
+        (
+            index,
+            (range.start.min(end) - start)..(range.end.min(end) - start),
+        )
+    }
+
+    /// returns the index of the expression containing `offset`,
+    /// or [`None`] if the offset is in a [`TplElement`]
+    pub fn expr_at(&self, offset: usize) -> Option<usize> {
+        let index = self
+            .quasi_ranges
+            .partition_point(|quasi| quasi.start <= offset)
+            .saturating_sub(1);
+
+        (offset >= self.quasi_ranges[index].end && index + 1 < self.quasi_ranges.len())
+            .then_some(index)
+    }
+}
+
+/// rebuilds `tpl` from `code`, its joined value with the `i`-th expression replaced by `substitutes[i]`,
+/// removing expressions whose substitutes are not found in `code`
+///
+/// A rebuilt [`TplElement`] spans the original ones that were merged into it with removed expressions
+pub fn rebuild_tpl(tpl: &mut Tpl, code: &str, substitutes: &[String]) {
+    let mut parts = Vec::with_capacity(tpl.quasis.len());
+    let mut kept = Vec::with_capacity(tpl.exprs.len());
+    let mut rest = code;
+
+    for (i, substitute) in substitutes.iter().enumerate() {
+        if let Some(pos) = rest.find(substitute.as_str()) {
+            parts.push(&rest[..pos]);
+            kept.push(i);
+            rest = &rest[pos + substitute.len()..];
+        }
+    }
+
+    parts.push(rest);
+
+    let quasis = parts
+        .iter()
+        .enumerate()
+        .map(|(j, part)| {
+            // a part starts either the template or after a kept expression
+            // and ends either the template or before a kept expression
+            let first = if j == 0 { 0 } else { kept[j - 1] + 1 };
+            let last = kept.get(j).copied().unwrap_or(tpl.quasis.len() - 1);
+
+            let mut tpl_el = TplElement {
+                span: Span::new(tpl.quasis[first].span.lo, tpl.quasis[last].span.hi),
+                tail: j + 1 == parts.len(),
+                cooked: None,
+                raw: Default::default(),
+            };
+            set_tpl_el_value(&mut tpl_el, part);
+            tpl_el
+        })
+        .collect();
+
+    tpl.quasis = quasis;
+    tpl.exprs = std::mem::take(&mut tpl.exprs)
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| kept.contains(i))
+        .map(|(_, expr)| expr)
+        .collect();
+}
+
+#[cfg(test)]
+mod test {
+    use swc_core::atoms::Atom;
+    use swc_core::common::{BytePos, DUMMY_SP, Span};
+    use swc_core::ecma::ast::{Expr, Ident, Tpl, TplElement};
+
+    use super::rebuild_tpl;
+
+    fn tpl_el(lo: u32, hi: u32, value: &str) -> TplElement {
+        TplElement {
+            span: Span::new(BytePos(lo), BytePos(hi)),
+            tail: false,
+            cooked: Some(Atom::new(value).into()),
+            raw: Atom::new(value),
+        }
+    }
+
+    #[test]
+    fn rebuilds_with_kept_expressions() {
+        // `a ${A} b ${B} c ${C} d`
+        let mut tpl = Tpl {
+            span: DUMMY_SP,
+            exprs: ["A", "B", "C"]
+                .map(|name| Box::new(Expr::Ident(Ident::new_no_ctxt(name.into(), DUMMY_SP))))
+                .into(),
+            quasis: vec![
+                tpl_el(1, 3, "a "),
+                tpl_el(7, 10, " b "),
+                tpl_el(14, 17, " c "),
+                tpl_el(21, 23, " d"),
+            ],
+        };
+        let substitutes = ["_0_", "_1_", "_2_"].map(ToOwned::to_owned);
+
+        rebuild_tpl(&mut tpl, r"a _0_ b c _2_ d \ ` ${", &substitutes);
+
+        let kept = tpl
+            .exprs
+            .iter()
+            .map(|expr| expr.as_ident().unwrap().sym.as_str())
+            .collect::<Vec<_>>();
+        let quasis = tpl
+            .quasis
+            .iter()
+            .map(|tpl_el| {
+                (
+                    tpl_el.span.lo.0..tpl_el.span.hi.0,
+                    tpl_el.raw.as_str(),
+                    tpl_el.tail,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(kept, ["A", "C"]);
+        assert_eq!(
+            quasis,
+            [
+                (1..3, "a ", false),
+                (7..17, " b c ", false),
+                (21..23, r" d \\ \` \${", true),
+            ]
+        );
+        assert_eq!(
+            tpl.quasis[2]
+                .cooked
+                .as_ref()
+                .and_then(|cooked| cooked.as_str()),
+            Some(r" d \ ` ${")
+        );
+    }
+}
@@ -1,68 +1,275 @@
 #![allow(clippy::default_trait_access, clippy::module_name_repetitions)]
 
+mod config;
+mod diagnostics;
+mod joined_tpl;
+mod marker;
 mod str_span;
+mod transform;
+mod validation;
 mod visitor;
 
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+
+use graphql_minify::{MinifyOptions, ParseOptions, Schema, TransformOptions, Whitespace};
+use swc_core::atoms::Atom;
 use swc_core::common::comments::Comments;
+use swc_core::common::errors::HANDLER;
+use swc_core::common::plugin::metadata::TransformPluginMetadataContextKind;
 use swc_core::common::{BytePos, Spanned};
-use swc_core::ecma::ast::{Program, Str, Tpl};
+use swc_core::ecma::ast::{Expr, Lit, Pat, Program, Str, Tpl, VarDeclarator};
 use swc_core::ecma::transforms::testing::test_inline;
 use swc_core::ecma::visit::{VisitMut, VisitMutWith, noop_visit_mut_type};
 use swc_core::plugin::plugin_transform;
 use swc_core::plugin::proxies::{PluginCommentsProxy, TransformPluginProgramMetadata};
 
-use crate::visitor::Minifier;
+use crate::config::{Config, Mode, SchemaConfig, Severity};
+use crate::diagnostics::{codes, diagnostic_id, report_invalid_schema};
+use crate::marker::Marker;
+use crate::transform::Transformer;
+use crate::validation::Validator;
+use crate::visitor::{Minifier, validate_str_kind, validate_tpl_kind};
 
 pub struct MinifyGraphqlVisitor<C: Comments> {
     comments: C,
+    mode: Mode,
     minifier: Minifier,
+    validator: Validator,
+    transformer: Transformer,
+    /// name of the binding the visited initializer is assigned to, if it is a literal
+    binding: Option<Atom>,
 }
 
 impl<C: Comments> MinifyGraphqlVisitor<C> {
+    #[cfg(test)]
     fn new(comments: C) -> Self {
+        Self::with_config(comments, &Config::default(), None)
+    }
+
+    #[cfg(test)]
+    fn with_mode(comments: C, mode: Mode) -> Self {
+        Self {
+            mode,
+            ..Self::with_config(comments, &Config::default(), None)
+        }
+    }
+
+    /// creates the visitor for the environment `env`
+    fn with_config(comments: C, config: &Config, env: Option<&str>) -> Self {
+        let whitespace = if config.strict_whitespace {
+            Whitespace::Strict
+        } else {
+            Whitespace::Lenient
+        };
+        let parse_options = ParseOptions {
+            whitespace,
+            dialect: config.dialect.into(),
+        };
+
         Self {
             comments,
-            minifier: Minifier::default(),
+            mode: config.mode.resolve(env),
+            minifier: Minifier::new(
+                MinifyOptions {
+                    recover: config.recover,
+                    whitespace,
+                    dialect: config.dialect.into(),
+                    strip_descriptions: config.strip_descriptions,
+                    remove_directives: config.remove_directives.clone(),
+                    // literals are transformed as a whole by `Transformer`, not in parts
+                    transform: TransformOptions::default(),
+                },
+                config.expr_breaks_token,
+            ),
+            validator: Validator::new(
+                parse_options.clone(),
+                config.validate_syntax,
+                config
+                    .schema
+                    .as_ref()
+                    .and_then(|schema| load_schema(schema, &parse_options)),
+                config.lint,
+            ),
+            transformer: Transformer::new(&config.transform, parse_options),
+            binding: None,
         }
     }
 
-    fn is_graphql(&self, span_lo: BytePos) -> bool {
-        self.comments
-            .get_leading(span_lo)
-            .as_ref()
-            .and_then(|c| c.first())
-            .is_some_and(|c| {
-                c.text
-                    .to_ascii_lowercase()
-                    .trim_matches(|c: char| c == '*' || c.is_whitespace())
-                    == "graphql"
-            })
+    /// returns the GraphQL marker from the leading comment, reporting its unknown options
+    fn marker(&self, span_lo: BytePos) -> Option<Marker> {
+        let comment = self.comments.get_leading(span_lo)?.into_iter().next()?;
+        let marker = Marker::parse(&comment.text)?;
+
+        if !marker.unknown_options.is_empty() {
+            HANDLER.with(|handler| {
+                handler
+                    .struct_span_warn(comment.span, "unknown GraphQL marker options")
+                    .code(diagnostic_id(codes::UNKNOWN_MARKER_OPTIONS, Severity::Warn))
+                    .span_label(comment.span, marker.unknown_options.join(", "))
+                    .emit();
+            });
+        }
+
+        Some(marker)
     }
 }
 
 impl<C: Comments> VisitMut for MinifyGraphqlVisitor<C> {
     noop_visit_mut_type!();
 
+    fn visit_mut_program(&mut self, n: &mut Program) {
+        self.validator.collect_constants(n);
+        n.visit_mut_children_with(self);
+        self.transformer.report_collisions();
+    }
+
+    fn visit_mut_var_declarator(&mut self, n: &mut VarDeclarator) {
+        n.name.visit_mut_with(self);
+
+        self.binding = match (&n.name, n.init.as_deref()) {
+            (Pat::Ident(binding), Some(Expr::Tpl(_) | Expr::Lit(Lit::Str(_)))) => {
+                Some(binding.sym.clone())
+            }
+            _ => None,
+        };
+
+        n.init.visit_mut_with(self);
+        self.binding = None;
+    }
+
     fn visit_mut_str(&mut self, n: &mut Str) {
-        if self.is_graphql(n.span_lo()) {
-            self.minifier.minify_str(n);
+        let binding = self.binding.take();
+        let Some(marker) = self.marker(n.span_lo()) else {
+            return;
+        };
+
+        let mode = marker.mode.unwrap_or(self.mode);
+
+        if mode == Mode::Off
+            || marker.kind.is_some_and(|kind| !validate_str_kind(n, kind))
+            || !self.validator.validate_str(n, marker.kind)
+        {
+            return;
+        }
+
+        self.transformer
+            .transform_str(n, &marker, binding.as_deref());
+
+        match mode {
+            Mode::Minify => self.minifier.minify_str(n, marker.overrides),
+            Mode::Format => self.minifier.format_str(n),
+            Mode::Off => {}
         }
     }
 
     fn visit_mut_tpl(&mut self, n: &mut Tpl) {
-        if self.is_graphql(n.span_lo()) {
-            self.minifier.minify_tpl(n);
+        let binding = self.binding.take();
+        let Some(marker) = self.marker(n.span_lo()) else {
+            return;
+        };
+
+        let mode = marker.mode.unwrap_or(self.mode);
+
+        if mode == Mode::Off
+            || marker.kind.is_some_and(|kind| !validate_tpl_kind(n, kind))
+            || !self.validator.validate_tpl(n, marker.kind)
+        {
+            return;
+        }
+
+        self.transformer
+            .transform_tpl(n, &marker, binding.as_deref());
+
+        match mode {
+            Mode::Minify => self.minifier.minify_tpl(n, marker.overrides),
+            Mode::Format => self.minifier.format_tpl(n),
+            Mode::Off => {}
         }
     }
 }
 
+/// Schema loaded for a previous module
+struct LoadedSchema {
+    config: SchemaConfig,
+    options: ParseOptions,
+    /// modification time of the SDL file when it was read
+    modified: Option<SystemTime>,
+    schema: Arc<Schema>,
+}
+
+/// schema shared by the modules of a build, since they are processed with the same config
+static LOADED_SCHEMA: Mutex<Option<LoadedSchema>> = Mutex::new(None);
+
+/// reads and parses the configured schema, reporting the errors
+///
+/// The schema is reused by the following modules until the config or the SDL file changes
+fn load_schema(config: &SchemaConfig, options: &ParseOptions) -> Option<Arc<Schema>> {
+    let modified = config.modified();
+    let mut loaded = LOADED_SCHEMA.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if let Some(loaded) = loaded.as_ref()
+        && loaded.config == *config
+        && loaded.options == *options
+        && loaded.modified == modified
+    {
+        return Some(Arc::clone(&loaded.schema));
+    }
+
+    let sdl = match config.read() {
+        Ok(sdl) => sdl,
+        Err(err) => {
+            report_invalid_schema(&format!("failed to read GraphQL schema: {err}"));
+            return None;
+        }
+    };
+
+    let schema = match Schema::parse(&sdl, options) {
+        Ok(schema) => Arc::new(schema),
+        Err(err) => {
+            let (line, column) = err.line_column(&sdl);
+            report_invalid_schema(&format!(
+                "failed to parse GraphQL schema at {line}:{column}: {err}"
+            ));
+            return None;
+        }
+    };
+
+    *loaded = Some(LoadedSchema {
+        config: config.clone(),
+        options: options.clone(),
+        modified,
+        schema: Arc::clone(&schema),
+    });
+
+    Some(schema)
+}
+
+/// # Panics
+///
+/// Panics if the plugin config is invalid.
 #[plugin_transform]
 #[must_use]
+#[allow(clippy::needless_pass_by_value)]
 pub fn swc_plugin_minify_graphql(
     mut program: Program,
-    _: TransformPluginProgramMetadata,
+    metadata: TransformPluginProgramMetadata,
 ) -> Program {
-    program.visit_mut_with(&mut MinifyGraphqlVisitor::new(PluginCommentsProxy));
+    let config = metadata
+        .get_transform_plugin_config()
+        .map(|config| {
+            serde_json::from_str::<Config>(&config)
+                .unwrap_or_else(|e| panic!("invalid config for swc-plugin-minify-graphql: {e}"))
+        })
+        .unwrap_or_default();
+
+    let env = metadata.get_context(&TransformPluginMetadataContextKind::Env);
+
+    program.visit_mut_with(&mut MinifyGraphqlVisitor::with_config(
+        PluginCommentsProxy,
+        &config,
+        env.as_deref(),
+    ));
     program
 }
 
@@ -407,3 +614,179 @@ test_inline!(
         `;
     "#
 );
+
+test_inline!(
+    Default::default(),
+    |tr| swc_core::ecma::visit::visit_mut_pass(MinifyGraphqlVisitor::with_mode(
+        tr.comments.clone(),
+        Mode::Format
+    )),
+    format_mode,
+    r"
+        const QUERY = /* GraphQL */ `query ($id: ID!) { image (id: $id) { ...img } } fragment img on Image { id url }`;
+        const FIELDS = /* GraphQL */ 'id url';
+    ",
+    r#"
+        const QUERY = `query($id: ID!) {
+  image(id: $id) {
+    ...img
+  }
+}
+
+fragment img on Image {
+  id
+  url
+}`;
+        const FIELDS = "id\nurl";
+    "#
+);
+
+test_inline!(
+    Default::default(),
+    |tr| swc_core::ecma::visit::visit_mut_pass(MinifyGraphqlVisitor::with_mode(
+        tr.comments.clone(),
+        Mode::Format
+    )),
+    format_mode_with_exprs,
+    r#"
+        const ENTITY = /* GraphQL */ `
+            id
+            image { ${IMAGE} previewUrl(format: "${FORMAT}") }
+            ${FIELDS}
+        `;
+        const QUERY = /* GraphQL */ `query { entity { ...entity } } ${ENTITY_FRAGMENT}`;
+    "#,
+    r#"
+        const ENTITY = `id
+image {
+  ${IMAGE}
+  previewUrl(format: "${FORMAT}")
+}
+${FIELDS}`;
+        const QUERY = `query {
+  entity {
+    ...entity
+  }
+}
+
+${ENTITY_FRAGMENT}`;
+    "#
+);
+
+test_inline!(
+    Default::default(),
+    |tr| swc_core::ecma::visit::visit_mut_pass(MinifyGraphqlVisitor::with_mode(
+        tr.comments.clone(),
+        Mode::Off
+    )),
+    off_mode,
+    r"
+        const QUERY = /* GraphQL */ `
+            query { id }
+        `;
+    ",
+    r"
+        const QUERY = `
+            query { id }
+        `;
+    "
+);
+
+test_inline!(
+    Default::default(),
+    |tr| swc_core::ecma::visit::visit_mut_pass(MinifyGraphqlVisitor::new(tr.comments.clone())),
+    typed_markers,
+    r"
+        const SCHEMA = /* GraphQL schema */ `
+            type Image {
+                id: ID!
+            }
+        `;
+        const QUERY = /* GraphQL query */ `
+            query { image { ...img } }
+            ${IMAGE_FRAGMENT}
+        `;
+        const FRAGMENT = /* GraphQL fragment */ `
+            id
+            url
+        `;
+    ",
+    r"
+        const SCHEMA = `type Image{id:ID!}`;
+        const QUERY = `query{image{...img}}${IMAGE_FRAGMENT}`;
+        const FRAGMENT = `id url`;
+    "
+);
+
+test_inline!(
+    Default::default(),
+    |tr| swc_core::ecma::visit::visit_mut_pass(MinifyGraphqlVisitor::new(tr.comments.clone())),
+    typed_markers_kind_mismatch,
+    r"
+        const SCHEMA = /* GraphQL schema */ `
+            query { image { id } }
+        `;
+        const QUERY = /* GraphQL operation */ `
+            id
+            url
+        `;
+    ",
+    r"
+        const SCHEMA = `
+            query { image { id } }
+        `;
+        const QUERY = `
+            id
+            url
+        `;
+    "
+);
+
+test_inline!(
+    Default::default(),
+    |tr| swc_core::ecma::visit::visit_mut_pass(MinifyGraphqlVisitor::new(tr.comments.clone())),
+    marker_options,
+    r"
+        const FORMATTED = /* GraphQL fragment: format */ `id image { url }`;
+        const UNCHANGED = /* GraphQL: off */ `
+            id
+        `;
+    ",
+    r"
+        const FORMATTED = `id
+image {
+  url
+}`;
+        const UNCHANGED = `
+            id
+        `;
+    "
+);
+
+#[cfg(test)]
+#[testing::fixture("tests/fixture/**/input.js")]
+#[allow(clippy::needless_pass_by_value)]
+fn fixture(input: std::path::PathBuf) {
+    use swc_core::ecma::transforms::testing::{FixtureTestConfig, test_fixture};
+
+    let config = std::fs::read_to_string(input.with_file_name("config.json"))
+        .map(|config| serde_json::from_str::<Config>(&config).unwrap())
+        .unwrap_or_default();
+
+    test_fixture(
+        Default::default(),
+        &|tr| {
+            swc_core::ecma::visit::visit_mut_pass(MinifyGraphqlVisitor::with_config(
+                tr.comments.clone(),
+                &config,
+                None,
+            ))
+        },
+        &input,
+        &input.with_file_name("output.js"),
+        FixtureTestConfig {
+            allow_error: true,
+            ..Default::default()
+        },
+    );
+}
@@ -0,0 +1,199 @@
+use graphql_minify::DefinitionKind;
+
+use crate::config::Mode;
+
+/// Parsed GraphQL marker comment
+///
+/// The marker consists of the case-insensitive `GraphQL` word, an optional [`DocumentKind`]
+/// and optional options after `:`, e.g. `/* GraphQL */`, `/* GraphQL schema */` or `/* GraphQL fragment: format */`
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Marker {
+    /// expected kind of the document
+    pub kind: Option<DocumentKind>,
+    /// mode overriding the configured one
+    pub mode: Option<Mode>,
+    /// transforms overriding the configured ones
+    pub overrides: Overrides,
+    /// options that are not recognized
+    pub unknown_options: Vec<String>,
+}
+
+impl Marker {
+    /// parses the text of a comment, returning [`None`] if it is not a GraphQL marker
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text
+            .trim_matches(|c: char| c == '*' || c.is_whitespace())
+            .to_ascii_lowercase();
+
+        let rest = text.strip_prefix("graphql")?;
+        let (kind, options) = rest.split_once(':').unwrap_or((rest, ""));
+
+        if !kind.is_empty() && !kind.starts_with(|c: char| c.is_whitespace()) {
+            return None;
+        }
+
+        let mut marker = Marker {
+            kind: match kind.trim() {
+                "" => None,
+                kind => Some(DocumentKind::parse(kind)?),
+            },
+            ..Default::default()
+        };
+
+        for option in options
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|option| !option.is_empty())
+        {
+            match option {
+                "minify" => marker.mode = Some(Mode::Minify),
+                "format" => marker.mode = Some(Mode::Format),
+                "off" => marker.mode = Some(Mode::Off),
+                _ if marker.overrides.set(option) => {}
+                _ => marker.unknown_options.push(option.to_owned()),
+            }
+        }
+
+        Some(marker)
+    }
+}
+
+/// Transforms enabled or disabled (with the `no-` prefix) by the [`Marker`], e.g. `strip-descriptions`
+/// or `no-add-typename`, [`None`] if configured ones apply
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Overrides {
+    pub strip_descriptions: Option<bool>,
+    pub remove_client_fields: Option<bool>,
+    pub add_typename: Option<bool>,
+}
+
+impl Overrides {
+    /// sets the transform of the option, returning whether the option is a transform
+    fn set(&mut self, option: &str) -> bool {
+        let (name, is_enabled) = match option.strip_prefix("no-") {
+            Some(name) => (name, false),
+            None => (option, true),
+        };
+
+        let transform = match name {
+            "strip-descriptions" => &mut self.strip_descriptions,
+            "remove-client-fields" => &mut self.remove_client_fields,
+            "add-typename" => &mut self.add_typename,
+            _ => return false,
+        };
+
+        *transform = Some(is_enabled);
+        true
+    }
+}
+
+/// Kind of a document declared by the [`Marker`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    /// type system document
+    Schema,
+    /// executable document with operations and, optionally, fragments
+    Operation,
+    /// fragment definitions or the contents of a selection set
+    Fragment,
+}
+
+impl DocumentKind {
+    fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "schema" => Some(Self::Schema),
+            "operation" | "query" | "mutation" | "subscription" => Some(Self::Operation),
+            "fragment" => Some(Self::Fragment),
+            _ => None,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Schema => "schema",
+            Self::Operation => "operation",
+            Self::Fragment => "fragment",
+        }
+    }
+
+    /// whether the document of this kind may contain a definition of the given kind
+    pub const fn allows(self, definition: DefinitionKind) -> bool {
+        matches!(
+            (self, definition),
+            (Self::Schema, DefinitionKind::TypeSystem)
+                | (
+                    Self::Operation,
+                    DefinitionKind::Operation | DefinitionKind::Fragment
+                )
+                | (
+                    Self::Fragment,
+                    DefinitionKind::Fragment | DefinitionKind::Selection
+                )
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DocumentKind, Marker, Overrides};
+    use crate::config::Mode;
+
+    #[test]
+    fn parses_plain_marker() {
+        assert_eq!(Marker::parse(" GraphQL "), Some(Marker::default()));
+        assert_eq!(Marker::parse("* *** gRaPhQl * "), Some(Marker::default()));
+        assert_eq!(Marker::parse("graphql:"), Some(Marker::default()));
+    }
+
+    #[test]
+    fn parses_kind_and_options() {
+        assert_eq!(
+            Marker::parse("* GraphQL schema *"),
+            Some(Marker {
+                kind: Some(DocumentKind::Schema),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            Marker::parse("GraphQL Query: format"),
+            Some(Marker {
+                kind: Some(DocumentKind::Operation),
+                mode: Some(Mode::Format),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            Marker::parse("GraphQL: off, unknown"),
+            Some(Marker {
+                mode: Some(Mode::Off),
+                unknown_options: vec![String::from("unknown")],
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn parses_overrides() {
+        assert_eq!(
+            Marker::parse("GraphQL: strip-descriptions, no-add-typename remove-client-fields"),
+            Some(Marker {
+                overrides: Overrides {
+                    strip_descriptions: Some(true),
+                    remove_client_fields: Some(true),
+                    add_typename: Some(false),
+                },
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            Marker::parse("GraphQL: no-minify").unwrap().unknown_options,
+            ["no-minify"]
+        );
+    }
+
+    #[test]
+    fn ignores_other_comments() {
+        assert_eq!(Marker::parse("GraphQLite"), None);
+        assert_eq!(Marker::parse("GraphQL query to fetch a user"), None);
+        assert_eq!(Marker::parse("eslint-disable"), None);
+    }
+}
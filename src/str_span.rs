@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use swc_core::common::{BytePos, Span};
 use swc_core::ecma::ast::{Str, TplElement};
 
@@ -6,6 +8,26 @@ pub trait StrSpan {
     fn outer_span(&self) -> Span;
     /// returns [`Span`] of the string excluding quotes
     fn value_span(&self) -> Span;
+    /// returns the source of the string excluding quotes, if it is known
+    fn raw_value(&self) -> Option<&str>;
+
+    /// returns [`Span`] of the source characters that produced the `range` of the cooked value
+    ///
+    /// Escape sequences, line continuations and normalized line terminators are taken into account,
+    /// so the span does not drift when the source differs from the value
+    fn cooked_span(&self, range: Range<usize>) -> Span {
+        let (start, end) = match self.raw_value() {
+            Some(raw) => {
+                let offsets = cooked_offsets(raw);
+                let raw_offset = |offset: usize| offsets.get(offset).copied().unwrap_or(raw.len());
+
+                (raw_offset(range.start), raw_offset(range.end))
+            }
+            None => (range.start, range.end),
+        };
+
+        self.value_span().from_inner_byte_pos(start, end)
+    }
 }
 
 impl StrSpan for Str {
@@ -18,6 +40,13 @@ impl StrSpan for Str {
 
         Span::new(self.span.lo + QUOTE_LEN, self.span.hi - QUOTE_LEN)
     }
+
+    fn raw_value(&self) -> Option<&str> {
+        const QUOTE_LEN: usize = 1;
+
+        let raw = self.raw.as_deref()?;
+        raw.get(QUOTE_LEN..raw.len().checked_sub(QUOTE_LEN)?)
+    }
 }
 
 impl StrSpan for TplElement {
@@ -30,4 +59,139 @@ impl StrSpan for TplElement {
     fn value_span(&self) -> Span {
         self.span
     }
+
+    fn raw_value(&self) -> Option<&str> {
+        Some(&self.raw)
+    }
+}
+
+/// returns offsets in `raw` for every byte offset of the cooked value (including its end)
+///
+/// Offsets of all bytes produced by a source character or an escape sequence point to its start
+fn cooked_offsets(raw: &str) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(raw.len() + 1);
+    let mut pos = 0;
+
+    while let Some(c) = raw[pos..].chars().next() {
+        let (raw_len, cooked_len) = match c {
+            '\\' => escape_len(&raw[pos + 1..]),
+            // `\r\n` and `\r` are normalized to `\n` in templates
+            '\r' if raw[pos + 1..].starts_with('\n') => (2, 1),
+            _ => (c.len_utf8(), c.len_utf8()),
+        };
+
+        offsets.extend(std::iter::repeat_n(pos, cooked_len));
+        pos += raw_len;
+    }
+
+    offsets.push(raw.len());
+    offsets
+}
+
+/// returns the length of the escape sequence (including `\`) and the length of its cooked value
+///
+/// `escape` is the source following `\`
+fn escape_len(escape: &str) -> (usize, usize) {
+    const BACKSLASH_LEN: usize = 1;
+
+    let Some(c) = escape.chars().next() else {
+        return (BACKSLASH_LEN, 0);
+    };
+
+    let (len, cooked_len) = match c {
+        // line continuations
+        '\r' if escape[1..].starts_with('\n') => (2, 0),
+        '\r' | '\n' | '\u{2028}' | '\u{2029}' => (c.len_utf8(), 0),
+        'x' => (3, hex_char_len(escape.get(1..3))),
+        'u' => unicode_escape_len(escape),
+        '0'..='7' => legacy_octal_escape_len(escape),
+        _ => (c.len_utf8(), c.len_utf8()),
+    };
+
+    (BACKSLASH_LEN + len, cooked_len)
+}
+
+/// returns the length of `u{X…}`, `uXXXX` or `uXXXX\uXXXX` surrogate pair and the length of its cooked value
+fn unicode_escape_len(escape: &str) -> (usize, usize) {
+    if escape[1..].starts_with('{') {
+        let close = escape.find('}').unwrap_or(escape.len() - 1);
+        return (close + 1, hex_char_len(escape.get(2..close)));
+    }
+
+    let code_unit = |range: Range<usize>| {
+        escape
+            .get(range)
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+    };
+
+    let is_surrogate_pair = code_unit(1..5).is_some_and(|code| (0xD800..0xDC00).contains(&code))
+        && escape[5..].starts_with("\\u")
+        && code_unit(7..11).is_some_and(|code| (0xDC00..0xE000).contains(&code));
+
+    if is_surrogate_pair {
+        (11, 4)
+    } else {
+        (5, hex_char_len(escape.get(1..5)))
+    }
+}
+
+/// returns the length of `0` or legacy octal escape sequence and the length of its cooked value
+fn legacy_octal_escape_len(escape: &str) -> (usize, usize) {
+    let max_len = if escape.starts_with(['0', '1', '2', '3']) {
+        3
+    } else {
+        2
+    };
+
+    let len = escape
+        .bytes()
+        .take(max_len)
+        .take_while(|b| (b'0'..=b'7').contains(b))
+        .count();
+
+    (len, code_len(u32::from_str_radix(&escape[..len], 8).ok()))
+}
+
+fn hex_char_len(digits: Option<&str>) -> usize {
+    code_len(digits.and_then(|digits| u32::from_str_radix(digits, 16).ok()))
+}
+
+/// returns the UTF-8 length of the code point, lone surrogates are encoded in 3 bytes as in WTF-8
+fn code_len(code: Option<u32>) -> usize {
+    const SURROGATE_LEN: usize = 3;
+
+    code.map_or(1, |code| {
+        char::from_u32(code).map_or(SURROGATE_LEN, char::len_utf8)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::cooked_offsets;
+
+    #[test]
+    fn maps_plain_values() {
+        assert_eq!(cooked_offsets(""), [0]);
+        assert_eq!(cooked_offsets("id é"), [0, 1, 2, 3, 3, 5]);
+    }
+
+    #[test]
+    fn maps_escape_sequences() {
+        // `i\n`
+        assert_eq!(cooked_offsets(r"\x69\n"), [0, 4, 6]);
+        // `é` encoded in 2 bytes
+        assert_eq!(cooked_offsets(r"é\u{e9}"), [0, 0, 2, 2, 8]);
+        // `😀` encoded in 4 bytes
+        assert_eq!(cooked_offsets(r"\uD83D\uDE00."), [0, 0, 0, 0, 12, 13]);
+        // escaped quotes and legacy octal escapes
+        assert_eq!(cooked_offsets(r#"\"\`\0\101\8"#), [0, 2, 4, 6, 10, 12]);
+    }
+
+    #[test]
+    fn maps_line_terminators() {
+        // line continuations are removed
+        assert_eq!(cooked_offsets("a\\\nb\\\r\nc"), [0, 3, 7, 8]);
+        // `\r\n` is normalized to `\n`
+        assert_eq!(cooked_offsets("a\r\nb"), [0, 1, 3, 4]);
+    }
 }
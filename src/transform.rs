@@ -0,0 +1,256 @@
+//! Transforms of literals applied before processing, e.g. removal of `@client` fields
+//!
+//! Transforms need the AST, so literals that cannot be parsed are left to the validation and are not transformed.
+//! Expressions of templates are replaced with placeholder names, which are seen as fields inside selection sets
+//! and as comments between definitions, e.g. for interpolated fragments. Expressions inside removed parts
+//! of the document are removed from the template as well. Literals with the contents of selection sets
+//! that would be left empty are reported and are not transformed.
+//!
+//! Anonymous operations are named after the bindings the literals are assigned to. Names of all operations
+//! of the module are kept, so that given names used by other operations are reported once the module is visited.
+
+use graphql_minify::ast::Definition;
+use graphql_minify::{
+    Bump, DefinitionKind, MinifyError, ParseOptions, TransformOptions, apply_edits, definitions,
+    parse, transform_edits,
+};
+use swc_core::atoms::Wtf8Atom;
+use swc_core::common::Span;
+use swc_core::ecma::ast::{Str, Tpl};
+
+use crate::config::{NameOperationsConfig, TransformConfig};
+use crate::diagnostics::{report_emptied_selection, report_name_collision};
+use crate::joined_tpl::{JoinedTpl, rebuild_tpl};
+use crate::marker::{DocumentKind, Marker, Overrides};
+use crate::validation::is_selection;
+use crate::visitor::{expr_placeholder, expr_placeholder_prefix};
+
+/// Operation of a literal of the module
+struct Operation {
+    name: String,
+    /// span of the literal
+    span: Span,
+    /// whether the name is given by the transform
+    is_given: bool,
+}
+
+pub(crate) struct Transformer {
+    arena: Bump,
+    options: TransformOptions,
+    parse_options: ParseOptions,
+    naming: Option<NameOperationsConfig>,
+    /// named operations of the transformed literals of the module
+    operations: Vec<Operation>,
+}
+
+impl Transformer {
+    pub fn new(config: &TransformConfig, parse_options: ParseOptions) -> Self {
+        Self {
+            arena: Bump::new(),
+            options: config.into(),
+            parse_options,
+            naming: config.name_operations.clone(),
+            operations: Vec::new(),
+        }
+    }
+
+    /// returns the configured transforms with the per-literal `overrides`,
+    /// or [`None`] if there is nothing to do
+    fn options(&self, overrides: Overrides) -> Option<TransformOptions> {
+        let options = TransformOptions {
+            remove_client_fields: overrides
+                .remove_client_fields
+                .unwrap_or(self.options.remove_client_fields),
+            add_typename: overrides.add_typename.unwrap_or(self.options.add_typename),
+            operation_name: None,
+        };
+
+        // names of operations are collected even if no transform is enabled
+        (options.is_enabled() || self.naming.is_some()).then_some(options)
+    }
+
+    /// transforms [`Str`] with the `marker` assigned to the `binding`, if any
+    pub fn transform_str(&mut self, str: &mut Str, marker: &Marker, binding: Option<&str>) {
+        let Some(options) = self.options(marker.overrides) else {
+            return;
+        };
+
+        if let Some(value) = str.value.as_str()
+            && let Ok(Some(transformed)) =
+                self.transform(value, marker.kind, &options, binding, str.span)
+        {
+            str.value = Wtf8Atom::new(transformed);
+            str.raw = None;
+        }
+    }
+
+    /// transforms [`Tpl`] with the `marker` assigned to the `binding`, if any, as a whole,
+    /// with expressions replaced by placeholders
+    pub fn transform_tpl(&mut self, tpl: &mut Tpl, marker: &Marker, binding: Option<&str>) {
+        let Some(options) = self.options(marker.overrides) else {
+            return;
+        };
+
+        let prefix = expr_placeholder_prefix(&tpl.quasis);
+        let mut substitutes = (0..tpl.exprs.len())
+            .map(|i| expr_placeholder(&prefix, i))
+            .collect::<Vec<_>>();
+
+        // every retry turns one more placeholder between definitions into a comment
+        let transformed = loop {
+            let code = JoinedTpl::new(&tpl.quasis, |i| &substitutes[i]).code;
+
+            match self.transform(&code, marker.kind, &options, binding, tpl.span) {
+                Ok(Some(transformed)) => break transformed,
+                Ok(None) => return,
+                Err(_) => {
+                    let Some(index) = top_level_placeholder(&code, &substitutes) else {
+                        return;
+                    };
+
+                    substitutes[index] = format!("#{}\n", substitutes[index]);
+                }
+            }
+        };
+
+        rebuild_tpl(tpl, &transformed, &substitutes);
+    }
+
+    /// keeps the operation `names` of the literal at `span`, with the `given` name of the only anonymous one
+    fn record(&mut self, names: Vec<Option<String>>, given: Option<String>, span: Span) {
+        let given = given.filter(|_| matches!(names.as_slice(), [None]));
+
+        let operations = names
+            .into_iter()
+            .flatten()
+            .map(|name| (name, false))
+            .chain(given.map(|name| (name, true)))
+            .map(|(name, is_given)| Operation {
+                name,
+                span,
+                is_given,
+            });
+
+        self.operations.extend(operations);
+    }
+
+    /// reports names given to anonymous operations that are used by other operations of the module
+    pub fn report_collisions(&self) {
+        let Some(naming) = &self.naming else {
+            return;
+        };
+
+        for (i, operation) in self.operations.iter().enumerate() {
+            if !operation.is_given {
+                continue;
+            }
+
+            let other = self
+                .operations
+                .iter()
+                .enumerate()
+                .find(|(j, other)| *j != i && other.name == operation.name);
+
+            if let Some((_, other)) = other {
+                report_name_collision(
+                    operation.span,
+                    other.span,
+                    &operation.name,
+                    naming.collision,
+                );
+            }
+        }
+    }
+
+    /// returns the transformed `code` of the literal at `span`, or [`None`] if nothing is changed
+    ///
+    /// The contents of selection sets are transformed as a query shorthand, which is never named
+    fn transform(
+        &mut self,
+        code: &str,
+        kind: Option<DocumentKind>,
+        options: &TransformOptions,
+        binding: Option<&str>,
+        span: Span,
+    ) -> Result<Option<String>, MinifyError> {
+        let Some(is_selection) = is_selection(code, kind) else {
+            return Ok(None);
+        };
+
+        let source = if is_selection {
+            format!("{{{code}}}")
+        } else {
+            code.to_owned()
+        };
+
+        let options = TransformOptions {
+            operation_name: self
+                .naming
+                .as_ref()
+                .zip(binding)
+                .filter(|_| !is_selection)
+                .and_then(|(naming, binding)| naming.name(binding)),
+            ..options.clone()
+        };
+
+        let parsed = parse(&source, &self.arena, &self.parse_options).map(|document| {
+            let names = document
+                .definitions
+                .iter()
+                .filter_map(|definition| match definition {
+                    Definition::Operation(operation) => {
+                        Some(operation.name.as_ref().map(|name| name.value.to_owned()))
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            (transform_edits(&source, &document, &options), names)
+        });
+
+        self.arena.reset();
+
+        let (edits, names) = parsed?;
+
+        if !is_selection {
+            self.record(names, options.operation_name, span);
+        }
+
+        if edits.is_empty() {
+            return Ok(None);
+        }
+
+        let transformed = apply_edits(&source, &edits);
+
+        if !is_selection {
+            return Ok(Some(transformed));
+        }
+
+        // the whole selection set is removed with all its selections, which would leave
+        // an empty selection set wherever the literal is interpolated
+        let Some(selections) = transformed
+            .strip_prefix('{')
+            .and_then(|transformed| transformed.strip_suffix('}'))
+        else {
+            report_emptied_selection(span);
+            return Ok(None);
+        };
+
+        Ok(Some(selections.to_owned()))
+    }
+}
+
+/// returns the index of the placeholder that is seen as the start of the contents of a selection set
+/// between definitions, e.g. an interpolated fragment
+fn top_level_placeholder(code: &str, substitutes: &[String]) -> Option<usize> {
+    let start = definitions(code)
+        .ok()?
+        .into_iter()
+        .find(|definition| definition.kind == DefinitionKind::Selection)?
+        .span
+        .start;
+
+    substitutes
+        .iter()
+        .position(|substitute| code[start..].starts_with(substitute.as_str()))
+}
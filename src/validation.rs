@@ -0,0 +1,398 @@
+//! Validation of literals
+//!
+//! The minifier works on tokens and happily minifies documents that cannot be parsed,
+//! e.g. with unbalanced braces or a dangling `...`, so literals are parsed before processing.
+//! Parsed documents are also validated against the schema, if it is configured,
+//! and usages of its deprecated elements are reported as warnings.
+//! Lint rules that need no schema, e.g. unused variables, are checked with configured severities.
+//!
+//! Expressions of templates are substituted with values of string constants declared
+//! at the top level of the module, e.g. fragments interpolated into queries.
+//! Templates with expressions whose values are unknown cannot be parsed and are not validated.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+
+use graphql_minify::{
+    Bump, DefinitionKind, Deprecation, Lint, LintRule, Linter, MinifyError, ParseOptions, Schema,
+    ValidationError,
+};
+use swc_core::common::{Span, Spanned};
+use swc_core::ecma::ast::{
+    Decl, Expr, Id, Lit, ModuleDecl, ModuleItem, Pat, Program, Stmt, Str, Tpl, VarDecl, VarDeclKind,
+};
+
+use crate::config::{LintConfig, Severity};
+use crate::diagnostics::{ErrorReport, ValueLocation, codes};
+use crate::joined_tpl::JoinedTpl;
+use crate::marker::DocumentKind;
+use crate::str_span::StrSpan;
+
+const SYNTAX_ERROR: &str = "invalid GraphQL syntax";
+const SCHEMA_ERROR: &str = "GraphQL document does not match the schema";
+const DEPRECATED_WARNING: &str = "GraphQL document uses deprecated schema elements";
+const LINT_MESSAGE: &str = "GraphQL document violates lint rules";
+
+pub(crate) struct Validator {
+    arena: Bump,
+    options: ParseOptions,
+    /// severity of syntax errors
+    syntax: Severity,
+    schema: Option<Arc<Schema>>,
+    lint: LintConfig,
+    /// linter of all literals of the module, with the rules that are not off
+    linter: Linter,
+    /// values of top-level string constants of the module
+    constants: HashMap<Id, String>,
+}
+
+impl Validator {
+    pub fn new(
+        options: ParseOptions,
+        syntax: Severity,
+        schema: Option<Arc<Schema>>,
+        lint: LintConfig,
+    ) -> Self {
+        let rules = LintRule::ALL
+            .into_iter()
+            .filter(|rule| lint.severity(*rule) != Severity::Off);
+
+        Self {
+            arena: Bump::new(),
+            options,
+            syntax,
+            schema,
+            lint,
+            linter: Linter::new(rules),
+            constants: HashMap::new(),
+        }
+    }
+
+    /// collects values of top-level string constants of the program
+    ///
+    /// Must be called before literals are processed, since the values are taken as written
+    pub fn collect_constants(&mut self, program: &Program) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let decls = match program {
+            Program::Module(module) => module
+                .body
+                .iter()
+                .filter_map(|item| match item {
+                    ModuleItem::Stmt(Stmt::Decl(Decl::Var(decl))) => Some(decl),
+                    ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => match &export.decl {
+                        Decl::Var(decl) => Some(decl),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+            Program::Script(script) => script
+                .body
+                .iter()
+                .filter_map(|stmt| match stmt {
+                    Stmt::Decl(Decl::Var(decl)) => Some(decl),
+                    _ => None,
+                })
+                .collect(),
+            #[cfg(swc_ast_unknown)]
+            _ => return,
+        };
+
+        for decl in decls {
+            self.collect_decl(decl);
+        }
+    }
+
+    fn collect_decl(&mut self, decl: &VarDecl) {
+        if decl.kind != VarDeclKind::Const {
+            return;
+        }
+
+        for declarator in &decl.decls {
+            if let Pat::Ident(binding) = &declarator.name
+                && let Some(init) = &declarator.init
+                && let Some(value) = self.resolve(init)
+            {
+                self.constants.insert(binding.to_id(), value);
+            }
+        }
+    }
+
+    /// returns the value of a string literal, a template or an identifier of a known constant
+    fn resolve(&self, expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Lit(Lit::Str(str)) => str.value.as_str().map(ToOwned::to_owned),
+            Expr::Tpl(tpl) => {
+                let values = self.resolve_exprs(tpl)?;
+                Some(JoinedTpl::new(&tpl.quasis, |i| &values[i]).code)
+            }
+            Expr::Ident(ident) => self.constants.get(&ident.to_id()).cloned(),
+            Expr::Paren(paren) => self.resolve(&paren.expr),
+            _ => None,
+        }
+    }
+
+    fn resolve_exprs(&self, tpl: &Tpl) -> Option<Vec<String>> {
+        tpl.exprs.iter().map(|expr| self.resolve(expr)).collect()
+    }
+
+    /// checks [`Str`], returning whether the literal can be processed
+    pub fn validate_str(&mut self, str: &Str, kind: Option<DocumentKind>) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+
+        let Some(value) = str.value.as_str() else {
+            return true;
+        };
+
+        let location = ValueLocation::whole(value);
+
+        match self.check(value, kind) {
+            Problems::None => true,
+            Problems::Syntax(err) => {
+                let mut report = self.syntax_report();
+                report.add(str, &location, &err, err.span().clone());
+                report.emit(str.outer_span());
+
+                self.syntax != Severity::Error
+            }
+            Problems::Document {
+                errors,
+                deprecations,
+                lints,
+            } => {
+                report_document(
+                    &errors,
+                    &deprecations,
+                    &lints,
+                    self.lint,
+                    str.outer_span(),
+                    |report, code, message, span| {
+                        report.add_message(str, &location, code, message, span);
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    /// checks [`Tpl`] with expressions substituted by the values of constants,
+    /// returning whether the literal can be processed
+    ///
+    /// Templates with expressions of unknown values are not checked
+    pub fn validate_tpl(&mut self, tpl: &Tpl, kind: Option<DocumentKind>) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+
+        let Some(values) = self.resolve_exprs(tpl) else {
+            return true;
+        };
+
+        let resolved = JoinedTpl::new(&tpl.quasis, |i| &values[i]);
+        // positions are shown in the document without expressions, as in other errors
+        let document = JoinedTpl::new(&tpl.quasis, |_| "");
+
+        // errors inside values of expressions are labeled on the expressions
+        let locate = |span: &Range<usize>| match resolved.expr_at(span.start) {
+            Some(index) => Err(tpl.exprs[index].span()),
+            None => Ok(resolved.locate(span.clone())),
+        };
+
+        match self.check(&resolved.code, kind) {
+            Problems::None => true,
+            Problems::Syntax(err) => {
+                let mut report = self.syntax_report();
+
+                match locate(err.span()) {
+                    Ok((index, span)) => report.add(
+                        &tpl.quasis[index],
+                        &ValueLocation::quasi(&document, index),
+                        &err,
+                        span,
+                    ),
+                    Err(expr_span) => {
+                        report.add_in_expr(expr_span, &err, &resolved.code[err.span().clone()]);
+                    }
+                }
+
+                report.emit(tpl.span);
+
+                self.syntax != Severity::Error
+            }
+            Problems::Document {
+                errors,
+                deprecations,
+                lints,
+            } => {
+                report_document(
+                    &errors,
+                    &deprecations,
+                    &lints,
+                    self.lint,
+                    tpl.span,
+                    |report, code, message, span| match locate(&span) {
+                        Ok((index, span)) => report.add_message(
+                            &tpl.quasis[index],
+                            &ValueLocation::quasi(&document, index),
+                            code,
+                            message,
+                            span,
+                        ),
+                        Err(expr_span) => report.add_message_in_expr(expr_span, code, message),
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.syntax != Severity::Off || self.schema.is_some() || self.linter.is_enabled()
+    }
+
+    /// parses `code` as a document or as the contents of a selection set,
+    /// validating the document against the schema and lint rules
+    ///
+    /// Lexing errors and unsupported syntax are reported by the minifier and are ignored
+    fn check(&mut self, code: &str, kind: Option<DocumentKind>) -> Problems {
+        if code.trim().is_empty() {
+            return Problems::None;
+        }
+
+        let Some(is_selection) = is_selection(code, kind) else {
+            return Problems::None;
+        };
+
+        let problems = if is_selection {
+            // the type of the selection set and the enclosing operation are unknown,
+            // so only the syntax is checked
+            match graphql_minify::parse_selections(code, &self.arena, &self.options) {
+                Err(err) => Problems::syntax(err, self.syntax),
+                Ok(_) => Problems::None,
+            }
+        } else {
+            match graphql_minify::parse(code, &self.arena, &self.options) {
+                Err(err) => Problems::syntax(err, self.syntax),
+                Ok(document) => {
+                    let (errors, deprecations) = match &self.schema {
+                        Some(schema) => schema.check(&document),
+                        None => (Vec::new(), Vec::new()),
+                    };
+                    let lints = self.linter.lint(&document);
+
+                    if errors.is_empty() && deprecations.is_empty() && lints.is_empty() {
+                        Problems::None
+                    } else {
+                        Problems::Document {
+                            errors,
+                            deprecations,
+                            lints,
+                        }
+                    }
+                }
+            }
+        };
+
+        self.arena.reset();
+        problems
+    }
+
+    fn syntax_report(&self) -> ErrorReport<'static> {
+        if self.syntax == Severity::Error {
+            ErrorReport::new(codes::INVALID_SYNTAX, SYNTAX_ERROR)
+        } else {
+            ErrorReport::warning(codes::INVALID_SYNTAX, SYNTAX_ERROR)
+        }
+    }
+}
+
+/// returns whether `code` is the contents of a selection set rather than a document,
+/// or [`None`] if it cannot be lexed
+pub(crate) fn is_selection(code: &str, kind: Option<DocumentKind>) -> Option<bool> {
+    let definitions = graphql_minify::definitions(code).ok()?;
+    let first = definitions.first().map(|definition| definition.kind);
+
+    Some(match kind {
+        Some(DocumentKind::Fragment) => first != Some(DefinitionKind::Fragment),
+        Some(DocumentKind::Schema | DocumentKind::Operation) => false,
+        None => first == Some(DefinitionKind::Selection),
+    })
+}
+
+/// Problems found in a literal
+enum Problems {
+    None,
+    Syntax(MinifyError),
+    /// mismatches with the schema, deprecated usages and lints of a syntactically valid document
+    Document {
+        errors: Vec<ValidationError>,
+        deprecations: Vec<Deprecation>,
+        lints: Vec<Lint>,
+    },
+}
+
+impl Problems {
+    /// returns the syntax error if syntax errors are reported
+    fn syntax(err: MinifyError, severity: Severity) -> Self {
+        match err {
+            MinifyError::SyntaxError(..) if severity != Severity::Off => Self::Syntax(err),
+            _ => Self::None,
+        }
+    }
+}
+
+/// reports schema errors, deprecation warnings and lints with their configured severities
+/// of the literal at `outer_span`, `add` labels a message with the given code at the span of the document
+fn report_document(
+    errors: &[ValidationError],
+    deprecations: &[Deprecation],
+    lints: &[Lint],
+    lint: LintConfig,
+    outer_span: Span,
+    add: impl Fn(&mut ErrorReport, &str, &dyn fmt::Display, Range<usize>),
+) {
+    let mut report = ErrorReport::new(codes::SCHEMA_MISMATCH, SCHEMA_ERROR);
+
+    for err in errors {
+        add(&mut report, err.code(), err, err.span().clone());
+    }
+
+    report.emit(outer_span);
+
+    let mut report = ErrorReport::warning(codes::DEPRECATED_USAGE, DEPRECATED_WARNING);
+
+    for deprecation in deprecations {
+        add(
+            &mut report,
+            deprecation.code(),
+            deprecation,
+            deprecation.span.clone(),
+        );
+    }
+
+    report.emit(outer_span);
+
+    for severity in [Severity::Error, Severity::Warn] {
+        let mut report = if severity == Severity::Error {
+            ErrorReport::new(codes::LINT, LINT_MESSAGE)
+        } else {
+            ErrorReport::warning(codes::LINT, LINT_MESSAGE)
+        };
+
+        for item in lints
+            .iter()
+            .filter(|item| lint.severity(item.rule) == severity)
+        {
+            add(&mut report, item.code(), item, item.span.clone());
+        }
+
+        report.emit(outer_span);
+    }
+}
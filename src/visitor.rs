@@ -1,4 +1,4 @@
-//! GraphQL minification helpers
+//! GraphQL minification and formatting helpers
 //!
 //! Logic is built on several axioms:
 //! - [`Tpl`] always contains at least one [`TplElement`]
@@ -24,10 +24,18 @@
 //! [`Punctuator`]: https://spec.graphql.org/October2021/#Punctuator
 // spell-checker: ignore idurl
 
+use std::ops::Range;
+
+use graphql_minify::{MinifyAllocator, MinifyError, MinifyOptions};
 use swc_core::atoms::{Atom, Wtf8Atom};
 use swc_core::common::errors::HANDLER;
+use swc_core::common::{Span, Spanned};
 use swc_core::ecma::ast::{Str, Tpl, TplElement};
 
+use crate::config::Severity;
+use crate::diagnostics::{ErrorReport, ValueLocation, codes, diagnostic_id, report_broken_token};
+use crate::joined_tpl::{JoinedTpl, rebuild_tpl};
+use crate::marker::{DocumentKind, Overrides};
 use crate::str_span::StrSpan;
 
 /// [`Punctuator`] characters
@@ -38,7 +46,8 @@ use crate::str_span::StrSpan;
 /// since the only [`Token`] whose beginning or end is `.` is `...`
 ///
 /// cases where [`Expr`] breaks [`Token`] (e.g. `some${LONG}FieldName`, `123.${FP}` or `"some. ${STR} string"`)
-/// are considered invalid and are not handled properly
+/// are considered invalid and are not handled properly, names and numbers broken this way are reported
+/// by [`report_broken_token`]
 ///
 /// </div>
 ///
@@ -49,48 +58,109 @@ const PUNCTUATORS: &[char] = &[
     '!', '$', '&', '(', ')', '.', ':', '@', '[', ']', '{', ',', '}',
 ];
 
-#[derive(Default)]
+const MINIFY_ERROR: &str = "failed to minify GraphQL";
+const RECOVERED_WARNING: &str = "GraphQL minified with invalid tokens left as is";
+const FORMAT_ERROR: &str = "failed to format GraphQL";
+
 pub(crate) struct Minifier {
-    alloc: graphql_minify::MinifyAllocator,
+    alloc: MinifyAllocator,
+    options: MinifyOptions,
+    /// configured [`MinifyOptions::strip_descriptions`], which may be overridden per literal
+    strip_descriptions: bool,
+    /// severity of [`report_broken_token`] diagnostics
+    expr_breaks_token: Severity,
 }
 
 impl Minifier {
-    /// minifies [`Str`]
-    pub fn minify_str(&mut self, str: &mut Str) {
+    pub fn new(options: MinifyOptions, expr_breaks_token: Severity) -> Self {
+        Self {
+            alloc: MinifyAllocator::default(),
+            strip_descriptions: options.strip_descriptions,
+            options,
+            expr_breaks_token,
+        }
+    }
+
+    /// applies the per-literal `overrides` of the configured options
+    fn apply(&mut self, overrides: Overrides) {
+        self.options.strip_descriptions = overrides
+            .strip_descriptions
+            .unwrap_or(self.strip_descriptions);
+    }
+
+    /// minifies [`Str`] with the per-literal `overrides`
+    pub fn minify_str(&mut self, str: &mut Str, overrides: Overrides) {
+        self.apply(overrides);
+
+        let mut report = self.minify_report();
+
         if let Some(value) = str.value.as_str()
-            && let Some(min) = self.try_minify(value, str)
+            && let Some(min) =
+                self.try_minify(value, str, &ValueLocation::whole(value), &mut report)
         {
             str.value = Wtf8Atom::new(min);
             str.raw = None;
         }
+
+        report.emit(str.outer_span());
     }
 
-    /// minifies [`Tpl`]
-    pub fn minify_tpl(&mut self, tpl: &mut Tpl) {
+    /// minifies [`Tpl`] with the per-literal `overrides`
+    ///
+    /// Errors of all [`TplElement`]s are reported in a single diagnostic
+    pub fn minify_tpl(&mut self, tpl: &mut Tpl, overrides: Overrides) {
+        self.apply(overrides);
+
+        let mut report = self.minify_report();
+
         // If there are no expressions, we take the shortest path and
         // minify the single `TplElement` without additional checks
 
         if tpl.exprs.is_empty() {
             if let Some(tpl_el) = tpl.quasis.get_mut(0)
-                && let Some(min) = self.try_minify(tpl_el_value(tpl_el), tpl_el)
+                && let value = tpl_el_value(tpl_el)
+                && let Some(min) =
+                    self.try_minify(value, tpl_el, &ValueLocation::whole(value), &mut report)
             {
-                tpl_el.raw = Atom::new(min);
-                tpl_el.cooked = Some(tpl_el.raw.clone().into());
+                set_tpl_el_value(tpl_el, &min);
             }
 
+            report.emit(tpl.span);
             return;
         }
 
+        self.remove_directives(tpl);
+
+        if self.expr_breaks_token != Severity::Off {
+            let in_literal = ends_in_literal(&tpl.quasis);
+
+            for (i, expr) in tpl.exprs.iter().enumerate() {
+                if !in_literal[i]
+                    && let Some((before, after)) = broken_token(
+                        tpl_el_value(&tpl.quasis[i]),
+                        tpl_el_value(&tpl.quasis[i + 1]),
+                    )
+                {
+                    report_broken_token(expr.span(), self.expr_breaks_token, before, after);
+                }
+            }
+        }
+
         // minify all `TplElement`s, surrounding expressions with spaces if necessary
 
         let mut expr_it = tpl.exprs.iter();
         let mut has_prev_expr = false;
         let last_quasis_index = tpl.quasis.len() - 1;
+        // errors are located in the whole document rather than in separate `TplElement`s
+        let joined = JoinedTpl::new(&tpl.quasis, |_| "");
 
         for (i, tpl_el) in tpl.quasis.iter_mut().enumerate() {
             let next_is_expr = expr_it.next().is_some();
+            let location = ValueLocation::quasi(&joined, i);
 
-            if let Some(mut min) = self.try_minify(tpl_el_value(tpl_el), tpl_el) {
+            if let Some(mut min) =
+                self.try_minify(tpl_el_value(tpl_el), tpl_el, &location, &mut report)
+            {
                 let is_empty = min.is_empty();
                 let mut is_space_inserted = false;
 
@@ -109,15 +179,173 @@ impl Minifier {
                     min.push(' ');
                 }
 
-                tpl_el.raw = Atom::new(min);
-                tpl_el.cooked = Some(tpl_el.raw.clone().into());
+                set_tpl_el_value(tpl_el, &min);
             }
 
             has_prev_expr = next_is_expr;
         }
+
+        report.emit(tpl.span);
+    }
+
+    /// removes the configured directives from [`Tpl`] as a whole, so that directives whose arguments
+    /// contain expressions are removed together with the expressions, instead of leaving parts of
+    /// the arguments in other [`TplElement`]s
+    fn remove_directives(&self, tpl: &mut Tpl) {
+        if self.options.remove_directives.is_empty() {
+            return;
+        }
+
+        let prefix = expr_placeholder_prefix(&tpl.quasis);
+        let substitutes = (0..tpl.exprs.len())
+            .map(|i| expr_placeholder(&prefix, i))
+            .collect::<Vec<_>>();
+        let code = JoinedTpl::new(&tpl.quasis, |i| &substitutes[i]).code;
+
+        // lexing errors are reported by the minification of the parts
+        let Ok(edits) = graphql_minify::directive_edits(&code, &self.options.remove_directives)
+        else {
+            return;
+        };
+
+        if !edits.is_empty() {
+            rebuild_tpl(
+                tpl,
+                &graphql_minify::apply_edits(&code, &edits),
+                &substitutes,
+            );
+        }
+    }
+
+    /// formats [`Str`]
+    pub fn format_str(&mut self, str: &mut Str) {
+        let mut report = ErrorReport::new(codes::FORMAT_FAILED, FORMAT_ERROR);
+
+        if let Some(value) = str.value.as_str()
+            && let Some(formatted) = self.try_process(
+                value,
+                str,
+                &ValueLocation::whole(value),
+                |code, alloc| graphql_minify::format(code, alloc).map_err(|err| vec![err]),
+                &mut report,
+            )
+        {
+            str.value = Wtf8Atom::new(formatted);
+            str.raw = None;
+        }
+
+        report.emit(str.outer_span());
+    }
+
+    /// formats [`Tpl`]
+    ///
+    /// Unlike minification, [`Tpl`] is formatted as a whole so that the indentation is consistent
+    /// across expressions: expressions are replaced with unique placeholder names,
+    /// and the formatted document is split back by them
+    pub fn format_tpl(&mut self, tpl: &mut Tpl) {
+        let placeholder = expr_placeholder_prefix(&tpl.quasis);
+        let joined = JoinedTpl::new(&tpl.quasis, |i| expr_placeholder(&placeholder, i));
+
+        if joined.code.trim().is_empty() {
+            return;
+        }
+
+        let formatted = match graphql_minify::format(&joined.code, &mut self.alloc) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                let (index, err_value_span) = joined.locate(err.span().clone());
+                let document = JoinedTpl::new(&tpl.quasis, |_| "");
+
+                let mut report = ErrorReport::new(codes::FORMAT_FAILED, FORMAT_ERROR);
+                report.add(
+                    &tpl.quasis[index],
+                    &ValueLocation::quasi(&document, index),
+                    &err,
+                    err_value_span,
+                );
+                report.emit(tpl.span);
+                return;
+            }
+        };
+
+        let mut parts = Vec::with_capacity(tpl.quasis.len());
+        let mut rest = formatted.as_str();
+
+        for i in 0..tpl.exprs.len() {
+            let placeholder = expr_placeholder(&placeholder, i);
+
+            // e.g. the expression is inside a comment, which is not kept by formatting
+            let Some(pos) = rest.find(&placeholder) else {
+                HANDLER.with(|handler| {
+                    handler
+                        .struct_span_err(tpl.span, FORMAT_ERROR)
+                        .code(diagnostic_id(codes::FORMAT_FAILED, Severity::Error))
+                        .span_label(
+                            tpl.exprs[i].span(),
+                            "expression is lost when formatting the document",
+                        )
+                        .help("move the expression out of comments")
+                        .emit();
+                });
+                return;
+            };
+
+            parts.push(&rest[..pos]);
+            rest = &rest[pos + placeholder.len()..];
+        }
+
+        parts.push(rest);
+
+        for (tpl_el, part) in tpl.quasis.iter_mut().zip(parts) {
+            set_tpl_el_value(tpl_el, part);
+        }
+    }
+
+    /// returns the report for minification errors, which are warnings when recovering from them
+    fn minify_report(&self) -> ErrorReport<'static> {
+        if self.options.recover {
+            ErrorReport::warning(codes::RECOVERED, RECOVERED_WARNING)
+        } else {
+            ErrorReport::new(codes::MINIFY_FAILED, MINIFY_ERROR)
+        }
+    }
+
+    fn try_minify<Str>(
+        &mut self,
+        code: &str,
+        str: &Str,
+        location: &ValueLocation,
+        report: &mut ErrorReport,
+    ) -> Option<String>
+    where
+        Str: StrSpan,
+    {
+        if code.is_empty() {
+            return None;
+        }
+
+        let (output, errors) =
+            match graphql_minify::minify_with_options(code, &mut self.alloc, &self.options) {
+                Ok(minified) => (Some(minified.output), minified.recovered),
+                Err(errors) => (None, errors),
+            };
+
+        for err in &errors {
+            report.add(str, location, err, err.span().clone());
+        }
+
+        output
     }
 
-    fn try_minify<Str>(&mut self, code: &str, str: &Str) -> Option<String>
+    /// processes `code` of the `str` value, adding all errors to `report`
+    fn try_process<Str>(
+        &mut self,
+        code: &str,
+        str: &Str,
+        location: &ValueLocation,
+        process: fn(&str, &mut MinifyAllocator) -> Result<String, Vec<MinifyError>>,
+        report: &mut ErrorReport,
+    ) -> Option<String>
     where
         Str: StrSpan,
     {
@@ -125,42 +353,180 @@ impl Minifier {
             return None;
         }
 
-        match graphql_minify::minify(code, &mut self.alloc) {
+        match process(code, &mut self.alloc) {
             Ok(min) => Some(min),
-            Err(err) => HANDLER.with(|handler| {
-                let err_value_span = err.span();
-                let is_single_byte_err_span = (err_value_span.end - err_value_span.start) == 1;
-
-                let err_file_span = str
-                    .value_span()
-                    .from_inner_byte_pos(err_value_span.start, err_value_span.end);
-
-                handler
-                    .struct_span_err(str.outer_span(), "failed to minify GraphQL")
-                    .span_label(
-                        err_file_span,
-                        if is_single_byte_err_span {
-                            format!("{} at {}", err.as_str(), err_file_span.lo.0)
-                        } else {
-                            format!(
-                                "{} at {}-{}",
-                                err.as_str(),
-                                err_file_span.lo.0,
-                                err_file_span.hi.0
-                            )
-                        },
-                    )
-                    .emit();
+            Err(errors) => {
+                for err in &errors {
+                    report.add(str, location, err, err.span().clone());
+                }
+
                 None
-            }),
+            }
         }
     }
 }
 
-fn tpl_el_value(tpl_el: &TplElement) -> &str {
+/// checks that [`Str`] is a document of the given kind
+pub(crate) fn validate_str_kind(str: &Str, kind: DocumentKind) -> bool {
+    str.value.as_str().is_none_or(|value| {
+        validate_kind(value, kind, str.outer_span(), |span| str.cooked_span(span))
+    })
+}
+
+/// checks that [`Tpl`] is a document of the given kind
+///
+/// Expressions are ignored, since their contents are unknown
+pub(crate) fn validate_tpl_kind(tpl: &Tpl, kind: DocumentKind) -> bool {
+    let joined = JoinedTpl::new(&tpl.quasis, |_| " ");
+
+    validate_kind(&joined.code, kind, tpl.span, |span| {
+        let (index, span) = joined.locate(span);
+        tpl.quasis[index].cooked_span(span)
+    })
+}
+
+/// checks that `code` is a document of the given kind, reporting definitions of other kinds
+///
+/// `file_span` maps a span of `code` to the span in the file
+fn validate_kind(
+    code: &str,
+    kind: DocumentKind,
+    outer_span: Span,
+    file_span: impl Fn(Range<usize>) -> Span,
+) -> bool {
+    // lexing errors are reported by the minifier
+    let Ok(definitions) = graphql_minify::definitions(code) else {
+        return true;
+    };
+
+    let mut unexpected = definitions
+        .into_iter()
+        .filter(|definition| !kind.allows(definition.kind))
+        .peekable();
+
+    if unexpected.peek().is_none() {
+        return true;
+    }
+
+    HANDLER.with(|handler| {
+        let mut diagnostic =
+            handler.struct_span_err(outer_span, &format!("expected GraphQL {}", kind.as_str()));
+        diagnostic.code(diagnostic_id(codes::KIND_MISMATCH, Severity::Error));
+
+        for definition in unexpected {
+            diagnostic.span_label(
+                file_span(definition.span),
+                format!("found {}", definition.kind.as_str()),
+            );
+        }
+
+        diagnostic.emit();
+    });
+
+    false
+}
+
+/// returns the parts of a GraphQL name or number that the expression between
+/// `before` and `after` [`TplElement`]s splits, if it is adjacent to such characters on both sides
+fn broken_token<'a>(before: &'a str, after: &'a str) -> Option<(&'a str, &'a str)> {
+    const SPREAD: &str = "...";
+
+    let is_token_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '.';
+
+    let before = &before[before.trim_end_matches(is_token_char).len()..];
+    let before = before.strip_prefix(SPREAD).unwrap_or(before);
+    let after = &after[..after.len() - after.trim_start_matches(is_token_char).len()];
+    let after = if after.starts_with(SPREAD) { "" } else { after };
+
+    (!before.is_empty() && !after.is_empty()).then_some((before, after))
+}
+
+/// returns whether the ends of `quasis` are inside strings, block strings or comments,
+/// in which expressions never break tokens
+///
+/// Values of expressions are unknown, so they are considered to not change the state
+fn ends_in_literal(quasis: &[TplElement]) -> Vec<bool> {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum State {
+        Code,
+        String,
+        BlockString,
+        Comment,
+    }
+
+    const BLOCK_STRING: &[u8] = br#"""""#;
+    const ESCAPED_BLOCK_STRING: &[u8] = br#"\""""#;
+
+    let mut state = State::Code;
+
+    quasis
+        .iter()
+        .map(|tpl_el| {
+            let bytes = tpl_el_value(tpl_el).as_bytes();
+            let mut i = 0;
+
+            while i < bytes.len() {
+                let rest = &bytes[i..];
+
+                (state, i) = match (state, rest[0]) {
+                    (State::Code, b'#') => (State::Comment, i + 1),
+                    (State::Code, b'"') if rest.starts_with(BLOCK_STRING) => {
+                        (State::BlockString, i + BLOCK_STRING.len())
+                    }
+                    (State::Code, b'"') => (State::String, i + 1),
+                    (State::String, b'\\') => (State::String, i + 2),
+                    (State::String, b'"' | b'\n' | b'\r') | (State::Comment, b'\n' | b'\r') => {
+                        (State::Code, i + 1)
+                    }
+                    (State::BlockString, _) if rest.starts_with(ESCAPED_BLOCK_STRING) => {
+                        (State::BlockString, i + ESCAPED_BLOCK_STRING.len())
+                    }
+                    (State::BlockString, _) if rest.starts_with(BLOCK_STRING) => {
+                        (State::Code, i + BLOCK_STRING.len())
+                    }
+                    _ => (state, i + 1),
+                };
+            }
+
+            state != State::Code
+        })
+        .collect()
+}
+
+/// returns a prefix of expression placeholders that does not occur in any of `quasis`
+pub(crate) fn expr_placeholder_prefix(quasis: &[TplElement]) -> String {
+    let mut prefix = String::from("__swc_minify_graphql_expr");
+
+    while quasis
+        .iter()
+        .any(|tpl_el| tpl_el_value(tpl_el).contains(&prefix))
+    {
+        prefix.push('_');
+    }
+
+    prefix
+}
+
+pub(crate) fn expr_placeholder(prefix: &str, index: usize) -> String {
+    format!("{prefix}_{index}__")
+}
+
+pub(crate) fn tpl_el_value(tpl_el: &TplElement) -> &str {
     tpl_el
         .cooked
         .as_ref()
         .and_then(|cooked| cooked.as_str())
         .unwrap_or(tpl_el.raw.as_str())
 }
+
+/// sets the value of [`TplElement`], escaping `\`, `` ` `` and `${` in its raw text,
+/// so that the template is cooked into the value at runtime
+pub(crate) fn set_tpl_el_value(tpl_el: &mut TplElement, value: &str) {
+    let raw = value
+        .replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace("${", "\\${");
+
+    tpl_el.raw = Atom::new(raw);
+    tpl_el.cooked = Some(Atom::new(value).into());
+}
@@ -0,0 +1,26 @@
+const FIELDS = /* GraphQL */ "id author { name }";
+
+const POST = /* GraphQL */ `
+    fragment post on Post {
+        ${FIELDS}
+        __typename
+    }
+`;
+
+const QUERY = /* GraphQL */ `
+    query Feed {
+        feed {
+            ...post
+            ... on Poll { options }
+        }
+        viewer { kind: __typename }
+    }
+
+    ${POST}
+`;
+
+const SUBSCRIPTION = /* GraphQL */ `
+    subscription OnPost {
+        ... on Subscription { postAdded { id } }
+    }
+`;
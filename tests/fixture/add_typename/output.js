@@ -0,0 +1,4 @@
+const FIELDS = /* GraphQL */ "id author{name __typename}";
+const POST = /* GraphQL */ `fragment post on Post{${FIELDS} __typename}`;
+const QUERY = /* GraphQL */ `query Feed{feed{...post...on Poll{options __typename}__typename}viewer{kind:__typename __typename}}${POST}`;
+const SUBSCRIPTION = /* GraphQL */ `subscription OnPost{...on Subscription{postAdded{id __typename}}}`;
@@ -0,0 +1,6 @@
+const FIELDS = /* GraphQL */ "id\n\x20\u{1F600} url";
+
+const IMAGE = /* GraphQL */ `
+    id \
+    \`url\`
+`;
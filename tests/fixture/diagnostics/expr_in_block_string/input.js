@@ -0,0 +1,8 @@
+const DESCRIPTION = 'Image';
+
+const SCHEMA = /* GraphQL */ `
+    """${DESCRIPTION}"""
+    type Image {
+        id: ID!
+    }
+`;
@@ -0,0 +1,7 @@
+const DESCRIPTION = 'Image';
+const SCHEMA = /* GraphQL */ `
+    """${DESCRIPTION}"""
+    type Image {
+        id: ID!
+    }
+`;
@@ -0,0 +1,6 @@
+const FORMAT = 'long';
+
+const IMAGE = /* GraphQL */ `
+    id
+    url(format: "${FORMAT}")
+`;
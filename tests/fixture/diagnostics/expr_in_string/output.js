@@ -0,0 +1,5 @@
+const FORMAT = 'long';
+const IMAGE = /* GraphQL */ `
+    id
+    url(format: "${FORMAT}")
+`;
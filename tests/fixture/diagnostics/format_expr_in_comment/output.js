@@ -0,0 +1,6 @@
+const QUERY = /* GraphQL */ `
+    query Feed {
+        # fields of ${TYPE}
+        feed { id }
+    }
+`;
@@ -0,0 +1,5 @@
+const IMAGE = /* GraphQL */ `
+    image(path: "C:\\images\\logo.png") {
+        url(format: "\\u{D83D}")
+    }
+`;
@@ -0,0 +1,8 @@
+const QUERY = /* GraphQL */ `
+    query {
+        image(format: 'long') {
+            id %
+            url(size: "small)
+        }
+    }
+`;
@@ -0,0 +1 @@
+const IMAGE = /* GraphQL */ "id url(format: 'long')";
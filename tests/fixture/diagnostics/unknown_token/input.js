@@ -0,0 +1,7 @@
+const QUERY = /* GraphQL */ `
+    query {
+        image(id: 1) {
+            id %
+        }
+    }
+`;
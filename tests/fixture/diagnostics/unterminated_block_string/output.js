@@ -0,0 +1,7 @@
+const SCHEMA = /* GraphQL */ `
+    """
+    Image description
+    type Image {
+        id: ID!
+    }
+`;
@@ -0,0 +1,6 @@
+const QUERY = /* GraphQL */ `
+    query {
+        image(format: "long
+        ") { id }
+    }
+`;
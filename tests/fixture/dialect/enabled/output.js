@@ -0,0 +1 @@
+const IMAGE = /* GraphQL */ `"Image fields" fragment Image($size:Int=100)on Image{id url(size:$size)?}`;
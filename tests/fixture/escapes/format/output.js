@@ -0,0 +1,12 @@
+const FIELDS = /* GraphQL */ "id";
+const QUERY = /* GraphQL */ `query Search {
+  search(text: "a \\"quoted\\" \\\\ \`tick\` \${not an expression}") {
+    ${FIELDS}
+    label(suffix: "\\n") @client
+    summary(separator: "\\u2014")
+  }
+}`;
+const DOCUMENT = /* GraphQL */ `"""Use \`search\` instead"""
+type Query {
+  find(text: String = "\\\\"): [String]
+}`;
@@ -0,0 +1,3 @@
+const FIELDS = /* GraphQL */ "id";
+const QUERY = /* GraphQL */ `query Search{search(text:"a \\"quoted\\" \\\\ \`tick\` \${not an expression}"){${FIELDS} summary(separator:"\\u2014")}}`;
+const DOCUMENT = /* GraphQL */ `"""Use \`search\` instead""" type Query{find(text:String="\\\\"):[String]}`;
@@ -0,0 +1,7 @@
+const FIELD = /* GraphQL */ `
+    id
+    some${LONG}FieldName
+    image { ...${IMAGE_FRAGMENT} url(size: ${SIZE}) }
+    ...${FRAGMENT}
+`;
+const FLOAT = /* GraphQL */ `12${DIGITS}.5`;
@@ -0,0 +1,6 @@
+const GREETING = /* GraphQL */ `
+    greeting(text: "Hello${NAME}world", escaped: "\\"a${X}b")
+    block(text: """
+        Hello${NAME}world ""
+    """)
+    # commented${OUT} field some ${LONG} FieldName`;
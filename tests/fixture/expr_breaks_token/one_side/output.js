@@ -0,0 +1,2 @@
+const FIELD = /* GraphQL */ `id ${SUFFIX} ${PREFIX} Name image{url(size:${SIZE})}`;
+const FLOAT = /* GraphQL */ `123.${FP}`;
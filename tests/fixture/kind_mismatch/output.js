@@ -0,0 +1,4 @@
+const SCHEMA = /* GraphQL schema */ `
+    type Image { id: ID! }
+    query { image { id } }
+`;
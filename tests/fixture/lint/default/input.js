@@ -0,0 +1,8 @@
+const QUERY = /* GraphQL */ `
+    query Image($id: ID!, $size: Int) {
+        image(id: $id) {
+            url(format: $format)
+            ...img
+        }
+    }
+`;
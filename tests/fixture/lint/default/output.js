@@ -0,0 +1 @@
+const QUERY = /* GraphQL */ `query Image($id:ID!$size:Int){image(id:$id){url(format:$format)...img}}`;
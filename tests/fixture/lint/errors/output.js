@@ -0,0 +1,5 @@
+const IMAGE = /* GraphQL */ `fragment img on Image{url(size:$size)}`;
+const ANONYMOUS = /* GraphQL */ "{image{id}}";
+const QUERY = /* GraphQL */ `query Images($ids:[ID!]$format:Format){images(ids:$ids){...img...video}}${IMAGE}`;
+const UNUSED = /* GraphQL */ `query Images($id:ID!$size:Int$format:Format){image(id:$id){...img}}${IMAGE}`;
+const SELECTION = /* GraphQL */ "url(size:$size)...img";
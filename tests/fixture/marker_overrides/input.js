@@ -0,0 +1,19 @@
+const SCHEMA = /* GraphQL schema: strip-descriptions */ `
+    "A user"
+    type User {
+        "The name"
+        name: String
+    }
+`;
+
+const QUERY = /* GraphQL query: remove-client-fields */ `
+    query User {
+        user { id isLoggedIn @client }
+    }
+`;
+
+const PLAIN = /* GraphQL query: no-add-typename */ `
+    query Plain {
+        user { id }
+    }
+`;
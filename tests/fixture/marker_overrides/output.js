@@ -0,0 +1,3 @@
+const SCHEMA = /* GraphQL schema: strip-descriptions */ `type User{name:String}`;
+const QUERY = /* GraphQL query: remove-client-fields */ `query User{user{id __typename}}`;
+const PLAIN = /* GraphQL query: no-add-typename */ `query Plain{user{id}}`;
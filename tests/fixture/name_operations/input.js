@@ -0,0 +1,25 @@
+const USER_FIELDS = /* GraphQL */ "id name";
+
+export const GET_USER = /* GraphQL */ `
+    query ($id: ID!) {
+        user(id: $id) { ...user }
+    }
+
+    ${USER_FRAGMENT}
+`;
+
+const getFeed = /* GraphQL */ "{ feed { id } }";
+
+const OnPost = /* GraphQL */ `
+    subscription {
+        postAdded { id }
+    }
+`;
+
+const NAMED = /* GraphQL */ `query Named { viewer { id } }`;
+
+const GetFeed = /* GraphQL */ `query GetFeed { feed { id } }`;
+
+function useQuery() {
+    const $viewer = /* GraphQL */ `{ viewer { id } }`;
+}
@@ -0,0 +1,9 @@
+const USER_FIELDS = /* GraphQL */ "id name";
+export const GET_USER = /* GraphQL */ `query GetUser($id:ID!){user(id:$id){...user}}${USER_FRAGMENT}`;
+const getFeed = /* GraphQL */ "query GetFeed{feed{id}}";
+const OnPost = /* GraphQL */ `subscription OnPost{postAdded{id}}`;
+const NAMED = /* GraphQL */ `query Named{viewer{id}}`;
+const GetFeed = /* GraphQL */ `query GetFeed{feed{id}}`;
+function useQuery() {
+    const $viewer = /* GraphQL */ `query Viewer{viewer{id}}`;
+}
@@ -0,0 +1,6 @@
+const SCHEMA = /* GraphQL */ `
+    type Image {
+        id: ID!
+        url(size: Int = 1%): String
+    }
+`;
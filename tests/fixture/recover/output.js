@@ -0,0 +1 @@
+const SCHEMA = /* GraphQL */ `type Image{id:ID!url(size:Int=1 % ):String}`;
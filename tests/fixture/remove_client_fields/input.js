@@ -0,0 +1,22 @@
+const FIELDS = /* GraphQL */ "id isLiked @client";
+
+const LOCAL = /* GraphQL */ "isLoggedIn @client";
+
+const USER = /* GraphQL */ `
+    fragment user on User {
+        name
+        settings @client { ${FIELDS} }
+    }
+`;
+
+const QUERY = /* GraphQL */ `
+    query Feed($first: Int) {
+        feed(first: $first) {
+            ${FIELDS}
+            author { ...user }
+        }
+        cart @client { items }
+    }
+
+    ${USER}
+`;
@@ -0,0 +1,4 @@
+const FIELDS = /* GraphQL */ "id";
+const LOCAL = /* GraphQL */ "isLoggedIn@client";
+const USER = /* GraphQL */ `fragment user on User{name}`;
+const QUERY = /* GraphQL */ `query Feed($first:Int){feed(first:$first){${FIELDS} author{...user}}}${USER}`;
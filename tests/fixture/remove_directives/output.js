@@ -0,0 +1,6 @@
+const FIELDS = /* GraphQL */ "id isLiked";
+const QUERY = /* GraphQL */ `query Feed($first:Int){feed(first:$first){${FIELDS} author@include(if:true){name}}}`;
+const SCHEMA = /* GraphQL */ `directive@client on FIELD type Query{feed:[Post!]!@deprecated(reason:"Use posts")}`;
+const KEY = "feed";
+const FILTER = '["type"]';
+const PAGED = /* GraphQL */ `query Paged($first:Int){feed(first:$first){${FIELDS}}author@include(if:${true}){name}}`;
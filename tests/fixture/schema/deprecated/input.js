@@ -0,0 +1,9 @@
+// deprecations are reported as warnings, so the literal is minified
+const QUERY = /* GraphQL */ `
+    query {
+        image(id: 1, size: 100) {
+            url
+            src(format: GIF)
+        }
+    }
+`;
@@ -0,0 +1,3 @@
+const FIELDS = /* GraphQL */ "id size";
+const QUERY = /* GraphQL */ `fragment img on Image{${FIELDS}}`;
+const MUTATION = /* GraphQL */ "mutation{upload}";
@@ -0,0 +1,7 @@
+const QUERY = /* GraphQL */ `
+    query Image($id: ID!) {
+        image(id: $id) {
+            url
+        }
+    }
+`;
@@ -0,0 +1 @@
+const QUERY = /* GraphQL */ `query Image($id:ID!){image(id:$id){url}}`;
@@ -0,0 +1,4 @@
+const IMAGE_FIELDS = /* GraphQL */ `fragment img on Image{id url(format:PNG)}`;
+const VALID = /* GraphQL */ `query($id:ID!){image(id:$id){...img}search(term:"cat"){...on Video{duration}}}${IMAGE_FIELDS}`;
+const STALE = /* GraphQL */ `query($id:ID$size:String){image(id:$id size:$size width:100){...user...on Video{duration}alt}video{id}}fragment user on User{name}`;
+const UNKNOWN_TYPES = /* GraphQL */ `query($format:Formats){search(term:"cat"){...on Picture{url}}}`;
@@ -0,0 +1,5 @@
+const QUERY = /* GraphQL */ `
+    query {
+        image { id }
+    }
+`;
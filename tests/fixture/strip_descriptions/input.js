@@ -0,0 +1,35 @@
+const SCHEMA = /* GraphQL */ `
+    """
+    Image uploaded by a user
+    """
+    type Image {
+        "URL of the image"
+        url(
+            "width in pixels"
+            width: Int = 100
+            format: String = "png"
+        ): String! @deprecated(reason: "Use src")
+    }
+
+    ${DIRECTIVES}
+
+    "Output format"
+    enum Format {
+        "Portable Network Graphics"
+        PNG
+    }
+`;
+
+const QUERY = /* GraphQL */ `
+    query Image($alt: String = "image") {
+        image(alt: $alt, tags: ["a", "b"]) { url }
+    }
+`;
+
+const DOCUMENTED = /* GraphQL schema: no-strip-descriptions */ `
+    "A user"
+    type User {
+        "The name"
+        name: String
+    }
+`;
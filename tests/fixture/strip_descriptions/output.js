@@ -0,0 +1,3 @@
+const SCHEMA = /* GraphQL */ `type Image{url(width:Int=100 format:String="png"):String!@deprecated(reason:"Use src")}${DIRECTIVES} enum Format{PNG}`;
+const QUERY = /* GraphQL */ `query Image($alt:String="image"){image(alt:$alt tags:["a" "b"]){url}}`;
+const DOCUMENTED = /* GraphQL schema: no-strip-descriptions */ `"A user" type User{"The name" name:String}`;
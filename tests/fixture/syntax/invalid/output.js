@@ -0,0 +1,12 @@
+const UNBALANCED = /* GraphQL */ `
+    query {
+        image {
+            id
+    }
+`;
+const NO_SELECTION_SET = /* GraphQL */ "query Images";
+const DANGLING_SPREAD = /* GraphQL */ `
+    id
+    ...
+`;
+const VALID = /* GraphQL */ `id...img`;
@@ -0,0 +1,19 @@
+const IMAGE_FIELDS = /* GraphQL */ `id url`;
+const BROKEN_FIELDS = /* GraphQL */ "id ...";
+export const IMAGE_FRAGMENT = /* GraphQL */ `fragment img on Image{${IMAGE_FIELDS}}`;
+const QUERY = /* GraphQL */ `query{image{...img}}${IMAGE_FRAGMENT}`;
+const BROKEN_QUERY = /* GraphQL */ `
+    query {
+        image(id: ) {
+            ${IMAGE_FIELDS}
+        }
+    }
+`;
+const BROKEN_BY_EXPR = /* GraphQL */ `
+    query {
+        image {
+            ${BROKEN_FIELDS}
+        }
+    }
+`;
+const UNKNOWN = /* GraphQL */ `query{${IMPORTED}`;
@@ -0,0 +1 @@
+const NO_SELECTION_SET = /* GraphQL */ "query Images";